@@ -0,0 +1,247 @@
+use crate::{level, mode, replay, score, state, stats};
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::HashSet,
+};
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub const ACHIEVEMENTS_PATH: &str = "data/unlocks.achievements.ron";
+const ACHIEVEMENTS_KEY: &str = "achievements";
+
+//What has to happen for an achievement to unlock, the numbers come from the data file
+#[derive(Debug, Clone, Deserialize)]
+pub enum Condition {
+    //Lifetime totals
+    RunsPlayed(u64),
+    TotalBinned(u64),
+    //Catalog item name and how many of it
+    ItemBinned(String, u64),
+    ThrowsBinned(u64),
+    PlaytimeSeconds(f64),
+    //Within a single run
+    Score(u64),
+    Combo(u64),
+    Streak(u64),
+    RunSeconds(f32),
+    //A single throw, bank shots count with at least as many banks
+    Trick(level::trick::Trick),
+    TricksInOneThrow(usize),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub condition: Condition,
+    //Run conditions only count in this mode
+    #[serde(default)]
+    pub mode: Option<mode::GameMode>,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct AchievementList {
+    pub achievements: Vec<Achievement>,
+}
+
+#[derive(Resource)]
+pub struct AchievementListHandle(pub Handle<AchievementList>);
+
+//Ids of unlocked achievements, saved under "achievements"
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct UnlockedAchievements(pub HashSet<String>);
+
+#[derive(Event)]
+pub struct AchievementUnlockedEvent {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Error)]
+pub enum AchievementListLoaderError {
+    #[error("Could not read achievements: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse achievements: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+pub struct AchievementListLoader;
+
+impl AssetLoader for AchievementListLoader {
+    type Asset = AchievementList;
+    type Settings = ();
+    type Error = AchievementListLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(ron::de::from_bytes::<AchievementList>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["achievements.ron"]
+    }
+}
+
+//Everything an achievement can be checked against right now
+struct Progress<'a> {
+    lifetime: &'a stats::LifetimeStats,
+    run: &'a score::RunSummary,
+    throws: &'a [Vec<level::trick::Trick>],
+}
+
+impl Condition {
+    fn met(&self, progress: &Progress) -> bool {
+        let stats = &progress.run.stats;
+
+        match self {
+            Condition::RunsPlayed(runs) => progress.lifetime.runs_played >= *runs,
+            Condition::TotalBinned(count) => progress.lifetime.total_binned() >= *count,
+            Condition::ItemBinned(item, count) => {
+                progress.lifetime.binned.get(item).copied().unwrap_or(0) >= *count
+            }
+            Condition::ThrowsBinned(count) => progress.lifetime.throws_binned >= *count,
+            Condition::PlaytimeSeconds(seconds) => progress.lifetime.playtime >= *seconds,
+            Condition::Score(score) => stats.score >= *score,
+            Condition::Combo(combo) => stats.best_combo >= *combo,
+            Condition::Streak(streak) => stats.best_streak >= *streak,
+            Condition::RunSeconds(seconds) => progress.run.duration >= *seconds,
+            Condition::Trick(wanted) => {
+                progress
+                    .throws
+                    .iter()
+                    .flatten()
+                    .any(|trick| match (wanted, trick) {
+                        (
+                            level::trick::Trick::BankShot(wanted),
+                            level::trick::Trick::BankShot(banks),
+                        ) => banks >= wanted,
+                        _ => wanted == trick,
+                    })
+            }
+            Condition::TricksInOneThrow(count) => {
+                progress.throws.iter().any(|tricks| tricks.len() >= *count)
+            }
+        }
+    }
+
+    //Lifetime totals count in every mode, everything else is about the current run
+    fn per_run(&self) -> bool {
+        !matches!(
+            self,
+            Condition::RunsPlayed(_)
+                | Condition::TotalBinned(_)
+                | Condition::ItemBinned(..)
+                | Condition::ThrowsBinned(_)
+                | Condition::PlaytimeSeconds(_)
+        )
+    }
+}
+
+pub fn load_achievements(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pkv: Res<PkvStore>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+) {
+    commands.insert_resource(AchievementListHandle(asset_server.load(ACHIEVEMENTS_PATH)));
+
+    if let Ok(saved) = pkv.get::<UnlockedAchievements>(ACHIEVEMENTS_KEY) {
+        *unlocked = saved;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_achievements(
+    mut binned_event_reader: EventReader<level::types::TrashBinnedEvent>,
+    mut unlocked_event_writer: EventWriter<AchievementUnlockedEvent>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut pkv: ResMut<PkvStore>,
+    handle: Res<AchievementListHandle>,
+    lists: Res<Assets<AchievementList>>,
+    lifetime: Res<stats::LifetimeStats>,
+    summarizer: score::RunSummarizer,
+) {
+    let throws: Vec<Vec<level::trick::Trick>> = binned_event_reader
+        .read()
+        .map(|event| event.tricks.clone())
+        .collect();
+
+    let Some(list) = lists.get(&handle.0) else {
+        return;
+    };
+
+    let run = summarizer.summary();
+    let progress = Progress {
+        lifetime: &lifetime,
+        run: &run,
+        throws: &throws,
+    };
+
+    let mut changed = false;
+    for achievement in list.achievements.iter() {
+        if unlocked.0.contains(&achievement.id) {
+            continue;
+        }
+
+        let wrong_mode = achievement.condition.per_run()
+            && achievement
+                .mode
+                .is_some_and(|game_mode| game_mode != run.mode);
+        if wrong_mode || !achievement.condition.met(&progress) {
+            continue;
+        }
+
+        info!("Unlocked achievement {}", achievement.id);
+        unlocked.0.insert(achievement.id.clone());
+        unlocked_event_writer.send(AchievementUnlockedEvent {
+            name: achievement.name.clone(),
+            description: achievement.description.clone(),
+        });
+        changed = true;
+    }
+
+    if changed {
+        if let Err(e) = pkv.set(ACHIEVEMENTS_KEY, unlocked.as_ref()) {
+            error!("Failed to save achievements: {}", e);
+        }
+    }
+}
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AchievementList>()
+            .init_asset_loader::<AchievementListLoader>()
+            .init_resource::<UnlockedAchievements>()
+            .add_event::<AchievementUnlockedEvent>()
+            .add_systems(Startup, load_achievements)
+            .add_systems(
+                FixedUpdate,
+                evaluate_achievements
+                    .after(score::score_binned_trash)
+                    .after(stats::track_binned)
+                    .run_if(in_state(state::PauseState::Running))
+                    .run_if(not(replay::is_playback)),
+            )
+            //Runs played only goes up once the run is over
+            .add_systems(
+                OnEnter(state::PauseState::GameOver),
+                evaluate_achievements
+                    .after(stats::record_run)
+                    .run_if(not(replay::is_playback)),
+            );
+    }
+}
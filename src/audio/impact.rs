@@ -0,0 +1,205 @@
+use super::types;
+use crate::{level, rng};
+use avian2d::prelude::*;
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::HashMap,
+};
+use rand::Rng;
+use serde::Deserialize;
+use thiserror::Error;
+
+pub const IMPACTS_PATH: &str = "data/impacts.sounds.ron";
+
+//Change in speed of the lighter body, slower bumps stay silent and faster ones play at full strength
+const MIN_IMPACT: f32 = 20.0;
+const FULL_IMPACT: f32 = 150.0;
+//Seconds before the same two bodies may make a sound again, keeps resting stacks from buzzing
+const PAIR_COOLDOWN: f32 = 0.15;
+
+//Pairs of bodies that made a sound recently, with the time left before they may again
+#[derive(Resource, Default)]
+pub struct ImpactCooldowns(HashMap<(Entity, Entity), f32>);
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum ImpactSound {
+    Hit,
+    //One of the trash can thuds, picked at random
+    TrashCan,
+}
+
+//Sound, volume and pitch a pair of materials makes when it hits at full strength
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImpactRule {
+    //One body has to be one of these, empty matches anything
+    #[serde(default)]
+    pub with: Vec<level::types::Material>,
+    //The other body has to be one of these, empty matches anything
+    #[serde(default)]
+    pub against: Vec<level::types::Material>,
+    pub sound: ImpactSound,
+    pub volume: f32,
+    pub pitch: f32,
+}
+
+impl ImpactRule {
+    fn matches(&self, a: level::types::Material, b: level::types::Material) -> bool {
+        let is = |materials: &[level::types::Material], material| {
+            materials.is_empty() || materials.contains(&material)
+        };
+
+        (is(&self.with, a) && is(&self.against, b)) || (is(&self.with, b) && is(&self.against, a))
+    }
+}
+
+//Checked top to bottom, the first rule that matches both bodies plays
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct ImpactTable {
+    pub rules: Vec<ImpactRule>,
+}
+
+impl ImpactTable {
+    fn sound(
+        &self,
+        a: level::types::Material,
+        b: level::types::Material,
+        rng: &mut impl Rng,
+    ) -> Option<(types::SoundId, f32, f32)> {
+        let rule = self.rules.iter().find(|rule| rule.matches(a, b))?;
+        let sound = match rule.sound {
+            ImpactSound::Hit => types::SoundId::Hit,
+            ImpactSound::TrashCan => types::SoundId::TrashCan(rng.gen_range(1..=4)),
+        };
+
+        Some((sound, rule.volume, rule.pitch))
+    }
+}
+
+#[derive(Resource)]
+pub struct ImpactTableHandle(pub Handle<ImpactTable>);
+
+#[derive(Debug, Error)]
+pub enum ImpactTableLoaderError {
+    #[error("Could not read impact sounds: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse impact sounds: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+pub struct ImpactTableLoader;
+
+impl AssetLoader for ImpactTableLoader {
+    type Asset = ImpactTable;
+    type Settings = ();
+    type Error = ImpactTableLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(ron::de::from_bytes::<ImpactTable>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["sounds.ron"]
+    }
+}
+
+pub fn load_impact_table(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ImpactTableHandle(asset_server.load(IMPACTS_PATH)));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn play_impact_sounds(
+    time: Res<Time>,
+    mut collision_event_reader: EventReader<Collision>,
+    body_query: Query<(
+        &level::types::SurfaceMaterial,
+        &Position,
+        &Rotation,
+        Option<&Mass>,
+    )>,
+    mut cooldowns: ResMut<ImpactCooldowns>,
+    table_handle: Res<ImpactTableHandle>,
+    tables: Res<Assets<ImpactTable>>,
+    mut game_rng: ResMut<rng::GameRng>,
+    mut sfx_event_writer: EventWriter<types::PlaySfx>,
+) {
+    cooldowns.0.retain(|_, left| {
+        *left -= time.delta_seconds();
+        *left > 0.0
+    });
+
+    let Some(table) = tables.get(&table_handle.0) else {
+        return;
+    };
+
+    for Collision(contacts) in collision_event_reader.read() {
+        let pair = if contacts.entity1 < contacts.entity2 {
+            (contacts.entity1, contacts.entity2)
+        } else {
+            (contacts.entity2, contacts.entity1)
+        };
+        if cooldowns.0.contains_key(&pair) {
+            continue;
+        }
+
+        let Ok([(material1, position1, rotation1, mass1), (material2, _, _, mass2)]) =
+            body_query.get_many([contacts.entity1, contacts.entity2])
+        else {
+            continue;
+        };
+
+        //Static bodies have no mass, the lighter moving body is the one that feels the hit
+        let Some(mass) = [mass1, mass2]
+            .into_iter()
+            .flatten()
+            .map(|mass| mass.0)
+            .filter(|&mass| mass > 0.0 && mass.is_finite())
+            .reduce(f32::min)
+        else {
+            continue;
+        };
+
+        let impact = contacts.total_normal_impulse / mass;
+        if impact < MIN_IMPACT {
+            continue;
+        }
+        let strength = ((impact - MIN_IMPACT) / (FULL_IMPACT - MIN_IMPACT)).min(1.0);
+
+        let Some(contact) = contacts
+            .manifolds
+            .iter()
+            .flat_map(|manifold| manifold.contacts.iter())
+            .max_by(|a, b| a.normal_impulse.total_cmp(&b.normal_impulse))
+        else {
+            continue;
+        };
+        let point = contact.global_point1(position1, rotation1);
+
+        let Some((sound, volume, pitch)) =
+            table.sound(material1.0, material2.0, &mut game_rng.cosmetic)
+        else {
+            continue;
+        };
+
+        sfx_event_writer.send(
+            types::PlaySfx::new(sound)
+                .with_volume(volume * (0.3 + 0.7 * strength))
+                .with_pitch(pitch * (0.9 + 0.2 * strength))
+                .with_pitch_variance(0.05)
+                .with_priority((strength * 100.0) as u8)
+                .at_x(point.x),
+        );
+
+        cooldowns.0.insert(pair, PAIR_COOLDOWN);
+    }
+}
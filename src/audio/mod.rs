@@ -0,0 +1,209 @@
+pub mod impact;
+pub mod types;
+
+use crate::{rendering, rng, settings, state};
+use bevy::{
+    audio::{PlaybackMode, SpatialScale, Volume},
+    prelude::*,
+};
+use rand::Rng;
+
+//Gain change per second while the music ducks or comes back
+const DUCK_SPEED: f32 = 2.0;
+
+pub struct AudioManagerPlugin;
+
+impl Plugin for AudioManagerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<types::PlaySfx>()
+            .init_asset::<impact::ImpactTable>()
+            .init_asset_loader::<impact::ImpactTableLoader>()
+            .init_resource::<types::MusicDucking>()
+            .init_resource::<impact::ImpactCooldowns>()
+            .add_systems(Startup, (spawn_listener, impact::load_impact_table))
+            .add_systems(OnEnter(state::GameState::Menu), start_music)
+            .add_systems(Update, fade_ducking)
+            .add_systems(
+                FixedUpdate,
+                impact::play_impact_sounds.run_if(in_state(state::PauseState::Running)),
+            )
+            .add_systems(PostUpdate, (play_sfx, update_voice_volumes));
+    }
+}
+
+fn voice_gain(
+    voice: &types::Voice,
+    settings: &settings::Settings,
+    ducking: &types::MusicDucking,
+) -> f32 {
+    let duck = if voice.bus == types::Bus::Music {
+        ducking.current
+    } else {
+        1.0
+    };
+
+    voice.volume * voice.bus.gain(settings) * duck
+}
+
+//The volume of the playback settings is filled in from the voice
+fn spawn_voice(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    voice: types::Voice,
+    playback: PlaybackSettings,
+    settings: &settings::Settings,
+    ducking: &types::MusicDucking,
+) -> Entity {
+    commands
+        .spawn(AudioBundle {
+            source: asset_server.load(voice.sound.path()),
+            settings: PlaybackSettings {
+                volume: Volume::new(voice_gain(&voice, settings, ducking)),
+                ..playback
+            },
+        })
+        .insert(voice)
+        .id()
+}
+
+//Ears sit on the arena's side walls, so a sound at a wall plays from that speaker only
+pub fn spawn_listener(mut commands: Commands) {
+    commands.spawn((
+        TransformBundle::default(),
+        SpatialListener::new(rendering::types::RES_WIDTH as f32),
+    ));
+}
+
+//Menu music loops for the whole session, coming back to the menu keeps the same track going
+pub fn start_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    music_query: Query<(), With<types::GlobalMusic>>,
+    settings: Res<settings::Settings>,
+    ducking: Res<types::MusicDucking>,
+) {
+    if !music_query.is_empty() {
+        return;
+    }
+
+    let sound = types::SoundId::Music;
+    let music = spawn_voice(
+        &mut commands,
+        &asset_server,
+        types::Voice {
+            sound,
+            bus: sound.bus(),
+            volume: sound.volume(),
+            priority: u8::MAX,
+        },
+        PlaybackSettings::LOOP,
+        &settings,
+        &ducking,
+    );
+    commands.entity(music).insert(types::GlobalMusic);
+}
+
+pub fn play_sfx(
+    mut commands: Commands,
+    mut sfx_event_reader: EventReader<types::PlaySfx>,
+    voice_query: Query<(Entity, &types::Voice)>,
+    asset_server: Res<AssetServer>,
+    settings: Res<settings::Settings>,
+    ducking: Res<types::MusicDucking>,
+    mut game_rng: ResMut<rng::GameRng>,
+) {
+    //Voices started this frame count towards the caps as well
+    let mut playing: Vec<(Entity, types::SoundId, u8)> = voice_query
+        .iter()
+        .map(|(entity, voice)| (entity, voice.sound, voice.priority))
+        .collect();
+
+    for event in sfx_event_reader.read() {
+        let same: Vec<usize> = playing
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, sound, _))| sound.same_sound(&event.sound))
+            .map(|(index, _)| index)
+            .collect();
+
+        if same.len() >= event.sound.max_voices() {
+            //Cut off the least important voice, unless it matters as much as the new one
+            let Some(&lowest) = same.iter().min_by_key(|&&index| playing[index].2) else {
+                continue;
+            };
+            if playing[lowest].2 >= event.priority {
+                continue;
+            }
+
+            commands.entity(playing[lowest].0).despawn();
+            playing.swap_remove(lowest);
+        }
+
+        let speed = event.pitch
+            * (1.0 + (game_rng.cosmetic.gen::<f32>() * 2.0 - 1.0) * event.pitch_variance);
+        let voice = spawn_voice(
+            &mut commands,
+            &asset_server,
+            types::Voice {
+                sound: event.sound,
+                bus: event.bus,
+                volume: event.sound.volume() * event.volume,
+                priority: event.priority,
+            },
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                speed,
+                spatial: event.position.is_some(),
+                //The arena maps onto the space between the listener's ears
+                spatial_scale: Some(SpatialScale::new_2d(
+                    1.0 / rendering::types::RES_WIDTH as f32,
+                )),
+                ..Default::default()
+            },
+            &settings,
+            &ducking,
+        );
+        if let Some(x) = event.position {
+            //Set the global transform as well, the sound may start before transforms propagate
+            let transform = Transform::from_xyz(x, 0.0, 0.0);
+            commands.entity(voice).insert(TransformBundle {
+                local: transform,
+                global: GlobalTransform::from(transform),
+            });
+        }
+        playing.push((voice, event.sound, event.priority));
+    }
+}
+
+pub fn fade_ducking(time: Res<Time>, mut ducking: ResMut<types::MusicDucking>) {
+    let target = ducking.target();
+    if ducking.current == target {
+        return;
+    }
+
+    let step = DUCK_SPEED * time.delta_seconds();
+    ducking.current = if ducking.current < target {
+        (ducking.current + step).min(target)
+    } else {
+        (ducking.current - step).max(target)
+    };
+}
+
+//Sinks only read their volume when they start, so playing voices are set again on any change
+pub fn update_voice_volumes(
+    settings: Res<settings::Settings>,
+    ducking: Res<types::MusicDucking>,
+    sink_query: Query<(&AudioSink, &types::Voice)>,
+    spatial_sink_query: Query<(&SpatialAudioSink, &types::Voice)>,
+) {
+    if !settings.is_changed() && !ducking.is_changed() {
+        return;
+    }
+
+    for (sink, voice) in sink_query.iter() {
+        sink.set_volume(voice_gain(voice, &settings, &ducking));
+    }
+    for (sink, voice) in spatial_sink_query.iter() {
+        sink.set_volume(voice_gain(voice, &settings, &ducking));
+    }
+}
@@ -0,0 +1,190 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::settings;
+
+//Every sound the game plays, paths and mix levels live here and nowhere else
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundId {
+    Hover,
+    Select,
+    Pickup,
+    LetGo,
+    Hit,
+    Spawn,
+    //One of the four trash can thuds
+    TrashCan(u8),
+    GameOver,
+    Music,
+}
+
+impl SoundId {
+    pub fn path(&self) -> String {
+        match self {
+            SoundId::Hover => "ui/sounds/hover.ogg".to_string(),
+            SoundId::Select => "ui/sounds/select.ogg".to_string(),
+            SoundId::Pickup => "sounds/game/pickup.ogg".to_string(),
+            SoundId::LetGo => "sounds/game/letgo.ogg".to_string(),
+            SoundId::Hit => "sounds/game/hit.ogg".to_string(),
+            SoundId::Spawn => "sounds/game/spawn.ogg".to_string(),
+            SoundId::TrashCan(variant) => format!("sounds/trash/trashcan_{}.ogg", variant),
+            SoundId::GameOver => "sounds/game/gameover.ogg".to_string(),
+            SoundId::Music => "sounds/game/nothingon.ogg".to_string(),
+        }
+    }
+
+    pub fn bus(&self) -> Bus {
+        match self {
+            SoundId::Hover | SoundId::Select => Bus::Ui,
+            SoundId::Music => Bus::Music,
+            _ => Bus::Sfx,
+        }
+    }
+
+    //Mix level before the event's own volume and the bus gain
+    pub fn volume(&self) -> f32 {
+        match self {
+            SoundId::TrashCan(_) => 0.7,
+            SoundId::Spawn => 0.75,
+            SoundId::Music => 0.2,
+            _ => 0.5,
+        }
+    }
+
+    //How many of this sound may play at once, the trash can variants share one cap
+    pub fn max_voices(&self) -> usize {
+        match self {
+            SoundId::GameOver | SoundId::Music => 1,
+            SoundId::Hover | SoundId::Select | SoundId::Spawn => 2,
+            SoundId::Pickup | SoundId::LetGo => 3,
+            SoundId::Hit | SoundId::TrashCan(_) => 4,
+        }
+    }
+
+    pub fn same_sound(&self, other: &SoundId) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bus {
+    Music,
+    Sfx,
+    Ui,
+}
+
+impl Bus {
+    //Gain the player picked for this bus, master volume included
+    pub fn gain(&self, settings: &settings::Settings) -> f32 {
+        settings.master_volume
+            * match self {
+                Bus::Music => settings.music_volume,
+                Bus::Sfx => settings.sfx_volume,
+                Bus::Ui => settings.ui_volume,
+            }
+    }
+}
+
+//Asks the audio manager for a one-shot, it picks the file, bus gain and whether a voice is free
+#[derive(Event, Debug, Clone)]
+pub struct PlaySfx {
+    pub sound: SoundId,
+    pub bus: Bus,
+    //Multiplier on the sound's own mix level
+    pub volume: f32,
+    //Playback speed before the random change, 2.0 is an octave up
+    pub pitch: f32,
+    //Largest random change to the playback speed either way, 0.1 is up to 10%
+    pub pitch_variance: f32,
+    //Arena x the sound comes from, panned between the speakers. None plays it centred
+    pub position: Option<f32>,
+    //When every voice of the sound is busy, a higher priority one cuts off a lower one
+    pub priority: u8,
+}
+
+impl PlaySfx {
+    pub fn new(sound: SoundId) -> Self {
+        Self {
+            sound,
+            bus: sound.bus(),
+            volume: 1.0,
+            pitch: 1.0,
+            pitch_variance: 0.0,
+            position: None,
+            priority: 0,
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_pitch(mut self, pitch: f32) -> Self {
+        self.pitch = pitch;
+        self
+    }
+
+    pub fn with_pitch_variance(mut self, pitch_variance: f32) -> Self {
+        self.pitch_variance = pitch_variance;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn at_x(mut self, x: f32) -> Self {
+        self.position = Some(x);
+        self
+    }
+}
+
+//A sound the manager is playing, its volume is kept before bus gain so it can be reapplied
+#[derive(Component)]
+pub struct Voice {
+    pub sound: SoundId,
+    pub bus: Bus,
+    pub volume: f32,
+    pub priority: u8,
+}
+
+#[derive(Component)]
+pub struct GlobalMusic;
+
+//Why the music is turned down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DuckReason {
+    GameOver,
+}
+
+//Music gain multipliers held while something wants the music quieter, the lowest one wins
+#[derive(Resource)]
+pub struct MusicDucking {
+    reasons: HashMap<DuckReason, f32>,
+    //Eases towards the target so the music never jumps
+    pub current: f32,
+}
+
+impl Default for MusicDucking {
+    fn default() -> Self {
+        Self {
+            reasons: HashMap::new(),
+            current: 1.0,
+        }
+    }
+}
+
+impl MusicDucking {
+    pub fn duck(&mut self, reason: DuckReason, gain: f32) {
+        self.reasons.insert(reason, gain);
+    }
+
+    pub fn release(&mut self, reason: DuckReason) {
+        self.reasons.remove(&reason);
+    }
+
+    pub fn target(&self) -> f32 {
+        self.reasons.values().copied().fold(1.0, f32::min)
+    }
+}
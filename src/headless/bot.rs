@@ -0,0 +1,151 @@
+use crate::{
+    level::types::{HeldObject, Trash, TrashCanSensor},
+    replay::{
+        self,
+        types::{PendingPointer, PointerId},
+    },
+    ui,
+};
+use bevy::prelude::*;
+
+//How fast the bot drags the cursor around, in world units per second
+const CURSOR_SPEED: f32 = 150.0;
+//Height above a bin sensor the bot lets go of trash
+const DROP_HEIGHT: f32 = 22.0;
+//Seconds the bot keeps trying one piece of trash before giving up on it
+const GIVE_UP_AFTER: f32 = 4.0;
+
+#[derive(Default, Clone, Copy)]
+enum BotTask {
+    #[default]
+    Idle,
+    Reaching(Entity),
+    Carrying(Entity),
+}
+
+#[derive(Resource, Default)]
+pub struct Bot {
+    task: BotTask,
+    task_time: f32,
+    skip: Option<Entity>,
+}
+
+impl Bot {
+    fn switch(&mut self, task: BotTask) {
+        self.task = task;
+        self.task_time = 0.0;
+    }
+}
+
+//Picks up the trash closest to the cursor and drags it over the matching bin
+#[allow(clippy::type_complexity)]
+pub fn drive_bot(
+    time: Res<Time>,
+    mut bot: ResMut<Bot>,
+    mut pending_input: ResMut<replay::types::PendingInput>,
+    ghost_cursor_query: Query<(&Transform, &ui::types::GhostCursor)>,
+    trash_query: Query<(Entity, &Transform, &Trash, Has<HeldObject>)>,
+    sensor_query: Query<(&Transform, &TrashCanSensor)>,
+) {
+    let pointer = pending_input.pointer(PointerId::Mouse);
+    let cursor_pos = pointer.cursor;
+    *pointer = PendingPointer {
+        cursor: cursor_pos,
+        ..Default::default()
+    };
+
+    bot.task_time += time.delta_seconds();
+
+    //The ghost cursor shows up the tick after the pointer does
+    let Some((ghost_cursor, _)) = ghost_cursor_query
+        .iter()
+        .find(|(_, ghost_cursor)| ghost_cursor.0 == PointerId::Mouse)
+    else {
+        return;
+    };
+
+    let target = match bot.task {
+        BotTask::Idle => {
+            let closest = trash_query
+                .iter()
+                .filter(|(entity, ..)| Some(*entity) != bot.skip)
+                .min_by(|(_, a, ..), (_, b, ..)| {
+                    let a = a.translation.xy().distance_squared(cursor_pos);
+                    let b = b.translation.xy().distance_squared(cursor_pos);
+                    a.total_cmp(&b)
+                });
+
+            if let Some((entity, ..)) = closest {
+                bot.switch(BotTask::Reaching(entity));
+            }
+            return;
+        }
+        BotTask::Reaching(entity) => {
+            let Ok((_, transform, _, held)) = trash_query.get(entity) else {
+                bot.switch(BotTask::Idle);
+                return;
+            };
+
+            if held {
+                bot.switch(BotTask::Carrying(entity));
+                pointer.held = true;
+                return;
+            }
+
+            //Only press once the ghost cursor has caught up with the pointer
+            let target = transform.translation.xy();
+            if ghost_cursor.translation.xy().distance(target) < 0.5 {
+                pointer.held = true;
+                pointer.pressed = true;
+            }
+            target
+        }
+        BotTask::Carrying(entity) => {
+            let Ok((_, _, trash, held)) = trash_query.get(entity) else {
+                bot.switch(BotTask::Idle);
+                return;
+            };
+
+            if !held {
+                bot.switch(BotTask::Idle);
+                return;
+            }
+
+            let Some((bin, _)) = sensor_query
+                .iter()
+                .find(|(_, sensor)| sensor.0 == trash.category)
+            else {
+                bot.skip = Some(entity);
+                pointer.released = true;
+                bot.switch(BotTask::Idle);
+                return;
+            };
+
+            let target = bin.translation.xy() + Vec2::Y * DROP_HEIGHT;
+            if cursor_pos.distance(target) < 1.0 {
+                pointer.released = true;
+                bot.switch(BotTask::Idle);
+                return;
+            }
+
+            pointer.held = true;
+            target
+        }
+    };
+
+    if bot.task_time > GIVE_UP_AFTER {
+        if let BotTask::Reaching(entity) | BotTask::Carrying(entity) = bot.task {
+            bot.skip = Some(entity);
+        }
+        pointer.released = true;
+        bot.switch(BotTask::Idle);
+        return;
+    }
+
+    let step = CURSOR_SPEED * time.delta_seconds();
+    pointer.cursor = cursor_pos + (target - cursor_pos).clamp_length_max(step);
+}
+
+pub fn reset_bot(mut bot: ResMut<Bot>) {
+    *bot = Bot::default();
+}
@@ -0,0 +1,252 @@
+pub mod bot;
+
+use crate::{audio, input, level, mode, replay, rng, score, state, ui};
+use avian2d::prelude::*;
+use bevy::{
+    app::ScheduleRunnerPlugin, asset::AssetMetaCheck, audio::AudioLoader, prelude::*,
+    state::app::StatesPlugin, time::TimeUpdateStrategy,
+};
+use std::time::Duration;
+
+//Simulated time per frame, one FixedUpdate tick at the default 64hz
+const FRAME_TIME: f64 = 1.0 / 64.0;
+
+//Settings read from `--runs`, `--seed`, `--level` and `--max-seconds`
+#[derive(Resource)]
+pub struct Simulation {
+    pub runs: usize,
+    pub first_seed: u64,
+    pub max_seconds: f32,
+}
+
+pub struct RunResult {
+    pub seed: u64,
+    pub score: u64,
+    pub sorted: u64,
+    pub missorted: u64,
+    pub duration: f32,
+    pub capped: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct RunResults(pub Vec<RunResult>);
+
+//True when launched with `--headless`
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == "--headless")
+}
+
+fn arg_value<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let mut args = std::env::args();
+
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next().and_then(|value| value.parse().ok());
+        }
+    }
+
+    None
+}
+
+//Runs the game without a window, audio or renderer as fast as possible and prints run statistics
+pub fn run() -> AppExit {
+    let simulation = Simulation {
+        runs: arg_value("--runs").unwrap_or(100).max(1),
+        first_seed: arg_value("--seed").unwrap_or(0),
+        max_seconds: arg_value("--max-seconds").unwrap_or(600.0),
+    };
+
+    App::new()
+        .add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::ZERO)),
+            bevy::log::LogPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+            StatesPlugin,
+            bevy::input::InputPlugin,
+            AssetPlugin {
+                mode: AssetMode::Unprocessed,
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            },
+            ImagePlugin::default_nearest(),
+            PhysicsPlugins::default().with_length_unit(8.0),
+        ))
+        .init_asset::<AudioSource>()
+        .init_asset_loader::<AudioLoader>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            FRAME_TIME,
+        )))
+        .insert_resource(simulation)
+        .add_plugins((
+            state::StatePlugin,
+            input::ActionsPlugin,
+            level::LevelPlugin::new(false),
+            mode::ModePlugin,
+            score::ScorePlugin,
+            rng::RngPlugin,
+            replay::ReplayPlugin::new(false),
+            HeadlessPlugin,
+        ))
+        .run()
+}
+
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(level) = arg_value("--level") {
+            app.insert_resource(level::layout::CurrentLevel(level));
+        }
+
+        app.add_event::<ui::types::GameOverEvent>()
+            //Nothing plays sounds here, the events just expire
+            .add_event::<audio::types::PlaySfx>()
+            .insert_resource(ui::loading::LoadingData::new(1))
+            .insert_resource(ui::loading::LoadingState::Loading)
+            .insert_resource(ui::loading::PipelinesReady(true))
+            .init_resource::<RunResults>()
+            .init_resource::<bot::Bot>()
+            .add_systems(Startup, start_simulation)
+            .add_systems(Update, ui::loading::update_loading_data)
+            .add_systems(OnEnter(state::GameState::Menu), next_run)
+            .add_systems(OnExit(state::PauseState::Loading), bot::reset_bot)
+            .add_systems(
+                FixedUpdate,
+                (
+                    bot::drive_bot
+                        .before(replay::sample_input)
+                        .run_if(not(replay::is_playback)),
+                    end_run,
+                )
+                    .run_if(in_state(state::PauseState::Running)),
+            )
+            .add_systems(
+                OnEnter(state::PauseState::GameOver),
+                record_run.after(replay::finish_run),
+            );
+    }
+}
+
+fn start_simulation(
+    simulation: Res<Simulation>,
+    mut seed_selection: ResMut<rng::SeedSelection>,
+    replay_mode: Res<replay::types::ReplayMode>,
+    mut next_state: ResMut<NextState<state::GameState>>,
+) {
+    //A replay already picked its own seed
+    if !replay_mode.is_playback() {
+        seed_selection.0 = Some(simulation.first_seed);
+    }
+
+    next_state.set(state::GameState::InGame);
+}
+
+//Ends runs that ran out of time or hit the time cap
+fn end_run(
+    mut game_over_event_reader: EventReader<ui::types::GameOverEvent>,
+    timer_query: Query<&level::types::TimeRemaining>,
+    simulation: Res<Simulation>,
+    mut next_state: ResMut<NextState<state::PauseState>>,
+) {
+    let capped = timer_query
+        .iter()
+        .any(|timer| timer.elapsed >= simulation.max_seconds);
+
+    if game_over_event_reader.read().count() != 0 || capped {
+        next_state.set(state::PauseState::GameOver);
+    }
+}
+
+fn record_run(
+    mut results: ResMut<RunResults>,
+    score: Res<score::PlayerScore>,
+    game_rng: Res<rng::GameRng>,
+    simulation: Res<Simulation>,
+    timer_query: Query<&level::types::TimeRemaining>,
+    mut next_state: ResMut<NextState<state::GameState>>,
+) {
+    let duration = timer_query.iter().map(|timer| timer.elapsed).sum::<f32>();
+
+    let result = RunResult {
+        seed: game_rng.seed(),
+        score: score.score,
+        sorted: score.sorted,
+        missorted: score.missorted,
+        duration,
+        capped: duration >= simulation.max_seconds,
+    };
+
+    info!(
+        "Run {} seed {}: score {} sorted {} wrong {} in {:.1}s{}",
+        results.0.len() + 1,
+        result.seed,
+        result.score,
+        result.sorted,
+        result.missorted,
+        result.duration,
+        if result.capped { " (capped)" } else { "" }
+    );
+    results.0.push(result);
+
+    next_state.set(state::GameState::Menu);
+}
+
+//Starts the next seed, or prints the statistics once every run is done
+fn next_run(
+    results: Res<RunResults>,
+    simulation: Res<Simulation>,
+    mut seed_selection: ResMut<rng::SeedSelection>,
+    replay_mode: Res<replay::types::ReplayMode>,
+    mut next_state: ResMut<NextState<state::GameState>>,
+) {
+    if results.0.len() >= simulation.runs || replay_mode.is_playback() {
+        print_statistics(&results.0);
+        next_state.set(state::GameState::Quitting);
+        return;
+    }
+
+    seed_selection.0 = Some(simulation.first_seed + results.0.len() as u64);
+    next_state.set(state::GameState::InGame);
+}
+
+fn print_statistics(results: &[RunResult]) {
+    let runs = results.len() as f32;
+    let mut scores: Vec<u64> = results.iter().map(|result| result.score).collect();
+    scores.sort_unstable();
+
+    let mean = |value: fn(&RunResult) -> f32| results.iter().map(value).sum::<f32>() / runs;
+    let sorted = results.iter().map(|result| result.sorted).sum::<u64>();
+    let missorted = results.iter().map(|result| result.missorted).sum::<u64>();
+
+    println!("Runs:      {}", results.len());
+    println!(
+        "Score:     mean {:.2}  median {}  min {}  max {}",
+        mean(|result| result.score as f32),
+        scores[scores.len() / 2],
+        scores[0],
+        scores[scores.len() - 1]
+    );
+    println!(
+        "Duration:  mean {:.1}s  min {:.1}s  max {:.1}s",
+        mean(|result| result.duration),
+        results
+            .iter()
+            .map(|result| result.duration)
+            .fold(f32::INFINITY, f32::min),
+        results
+            .iter()
+            .map(|result| result.duration)
+            .fold(0.0, f32::max)
+    );
+    println!(
+        "Sorting:   {} sorted  {} wrong  {:.1}% accuracy",
+        sorted,
+        missorted,
+        sorted as f32 / (sorted + missorted).max(1) as f32 * 100.0
+    );
+    println!(
+        "Capped:    {}",
+        results.iter().filter(|result| result.capped).count()
+    );
+}
@@ -0,0 +1,162 @@
+pub mod types;
+
+use bevy::{
+    input::{
+        mouse::{MouseScrollUnit, MouseWheel},
+        InputSystem,
+    },
+    prelude::*,
+    ui::UiSystem,
+};
+use bevy_pkv::PkvStore;
+
+//Touchpads scroll in pixels, this many make up one wheel line
+const PIXELS_PER_LINE: f32 = 50.0;
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<types::InputBindings>()
+            .init_resource::<types::ActionState>()
+            .add_systems(Startup, load_bindings)
+            .add_systems(
+                PreUpdate,
+                update_action_state
+                    .after(InputSystem)
+                    .before(UiSystem::Focus),
+            );
+    }
+}
+
+//Headless runs have no store and keep the defaults, actions added since the last save get theirs
+pub fn load_bindings(pkv: Option<Res<PkvStore>>, mut bindings: ResMut<types::InputBindings>) {
+    let Some(mut saved) = pkv.and_then(|pkv| pkv.get::<types::InputBindings>("bindings").ok())
+    else {
+        return;
+    };
+
+    for (action, defaults) in types::InputBindings::default().0 {
+        saved.0.entry(action).or_insert(defaults);
+    }
+
+    *bindings = saved;
+}
+
+pub fn save_bindings(pkv: &mut PkvStore, bindings: &types::InputBindings) {
+    if let Err(e) = pkv.set("bindings", bindings) {
+        error!("Failed to save bindings: {}", e);
+    }
+}
+
+//A tap that starts and ends within one frame still counts as a press followed by a release
+fn set_pressed(
+    state: &mut ButtonInput<types::Action>,
+    action: types::Action,
+    (pressed, just_pressed): (bool, bool),
+) {
+    if pressed || just_pressed {
+        state.press(action);
+    }
+    if !pressed {
+        state.release(action);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_action_state(
+    bindings: Res<types::InputBindings>,
+    mut action_state: ResMut<types::ActionState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut mouse_wheel_event_reader: EventReader<MouseWheel>,
+) {
+    let action_state = action_state.as_mut();
+
+    action_state.wheel = mouse_wheel_event_reader
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        })
+        .sum();
+
+    //Window y grows downwards, so the angle is flipped to count counter-clockwise
+    let twist = match touches.iter().collect::<Vec<_>>()[..] {
+        [a, b] => -(b.previous_position() - a.previous_position())
+            .angle_between(b.position() - a.position()),
+        _ => 0.0,
+    };
+    //Fingers on the same spot have no angle between them
+    action_state.twist = if twist.is_finite() { twist } else { 0.0 };
+
+    //Unplugged gamepads let go of everything they held
+    action_state.devices.retain(|device, _| match device {
+        types::InputDevice::Gamepad(gamepad) => gamepads.contains(*gamepad),
+        _ => true,
+    });
+
+    let mut devices = vec![types::InputDevice::KeyboardMouse, types::InputDevice::Touch];
+    devices.extend(gamepads.iter().map(types::InputDevice::Gamepad));
+
+    for device in devices {
+        let state = action_state.devices.entry(device).or_default();
+        state.clear();
+
+        for action in types::Action::ALL {
+            let any = |check: &dyn Fn(&types::Binding) -> (bool, bool)| {
+                bindings
+                    .get(action)
+                    .iter()
+                    .map(check)
+                    .fold((false, false), |(a, b), (c, d)| (a || c, b || d))
+            };
+
+            let pressed = match device {
+                types::InputDevice::KeyboardMouse => any(&|binding| match binding {
+                    types::Binding::Key(key) => (keys.pressed(*key), keys.just_pressed(*key)),
+                    types::Binding::Mouse(button) => (
+                        mouse_buttons.pressed(*button),
+                        mouse_buttons.just_pressed(*button),
+                    ),
+                    types::Binding::Gamepad(_) => (false, false),
+                }),
+                //Any finger on the screen grabs, what it grabs is up to the finger's own pointer
+                types::InputDevice::Touch if action == types::Action::Grab => {
+                    (touches.iter().next().is_some(), touches.any_just_pressed())
+                }
+                types::InputDevice::Touch => (false, false),
+                types::InputDevice::Gamepad(gamepad) => any(&|binding| match binding {
+                    types::Binding::Gamepad(button) => {
+                        let button = GamepadButton::new(gamepad, *button);
+                        (
+                            gamepad_buttons.pressed(button),
+                            gamepad_buttons.just_pressed(button),
+                        )
+                    }
+                    _ => (false, false),
+                }),
+            };
+
+            set_pressed(state, action, pressed);
+        }
+    }
+
+    action_state.combined.clear();
+    for action in types::Action::ALL {
+        let pressed =
+            action_state
+                .devices
+                .values()
+                .fold((false, false), |(pressed, just_pressed), state| {
+                    (
+                        pressed || state.pressed(action),
+                        just_pressed || state.just_pressed(action),
+                    )
+                });
+        set_pressed(&mut action_state.combined, action, pressed);
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+//Keys and buttons shown per action on the controls screen
+pub const BINDING_SLOTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Grab,
+    //Lets go of held trash even while Grab is still down
+    Release,
+    Pause,
+    RotateLeft,
+    RotateRight,
+    Restart,
+    Confirm,
+    Back,
+    FocusPrevious,
+    FocusNext,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::Grab,
+        Action::Release,
+        Action::Pause,
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::Restart,
+        Action::Confirm,
+        Action::Back,
+        Action::FocusPrevious,
+        Action::FocusNext,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Grab => "Grab",
+            Action::Release => "Release",
+            Action::Pause => "Pause",
+            Action::RotateLeft => "Rotate left",
+            Action::RotateRight => "Rotate right",
+            Action::Restart => "Restart",
+            Action::Confirm => "Confirm",
+            Action::Back => "Back",
+            Action::FocusPrevious => "Previous button",
+            Action::FocusNext => "Next button",
+        }
+    }
+
+    //Only read on menu screens, so they may share keys with the actions used in a run
+    pub fn is_menu(&self) -> bool {
+        matches!(
+            self,
+            Action::Confirm | Action::Back | Action::FocusPrevious | Action::FocusNext
+        )
+    }
+}
+
+//A single key or button an action can be bound to, touch always grabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key).trim_start_matches("Key").to_string(),
+            Binding::Mouse(button) => format!("Mouse {:?}", button),
+            Binding::Gamepad(button) => format!("Pad {:?}", button),
+        }
+    }
+}
+
+//Every action's keys and buttons, saved under "bindings"
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct InputBindings(pub HashMap<Action, Vec<Binding>>);
+
+impl InputBindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0
+            .get(&action)
+            .map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    //Puts the binding in one of the action's slots, a slot past the last binding adds it.
+    //Another action of the same kind that had the binding gets the replaced one instead, or loses it.
+    pub fn rebind(&mut self, action: Action, slot: usize, binding: Binding) {
+        let replaced = self.get(action).get(slot).copied();
+        if replaced == Some(binding) {
+            return;
+        }
+
+        for other in Action::ALL {
+            if other == action || other.is_menu() != action.is_menu() {
+                continue;
+            }
+
+            let Some(bindings) = self.0.get_mut(&other) else {
+                continue;
+            };
+            let Some(index) = bindings.iter().position(|bound| *bound == binding) else {
+                continue;
+            };
+
+            match replaced {
+                Some(replaced) if !bindings.contains(&replaced) => bindings[index] = replaced,
+                _ => {
+                    bindings.remove(index);
+                }
+            }
+        }
+
+        let bindings = self.0.entry(action).or_default();
+        match bindings.iter().position(|bound| *bound == binding) {
+            //Already in another slot of this action, the two trade places
+            Some(index) if slot < bindings.len() => bindings.swap(index, slot),
+            Some(_) => {}
+            None if slot < bindings.len() => bindings[slot] = binding,
+            None => bindings.push(binding),
+        }
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use GamepadButtonType as Pad;
+
+        Self(HashMap::from_iter([
+            (
+                Action::Grab,
+                vec![
+                    Binding::Mouse(MouseButton::Left),
+                    Binding::Gamepad(Pad::RightTrigger2),
+                    Binding::Gamepad(Pad::South),
+                ],
+            ),
+            (
+                Action::Release,
+                vec![
+                    Binding::Mouse(MouseButton::Right),
+                    Binding::Gamepad(Pad::East),
+                ],
+            ),
+            (
+                Action::Pause,
+                vec![Binding::Key(KeyCode::Escape), Binding::Gamepad(Pad::Start)],
+            ),
+            (
+                Action::RotateLeft,
+                vec![
+                    Binding::Key(KeyCode::KeyQ),
+                    Binding::Gamepad(Pad::LeftTrigger),
+                ],
+            ),
+            (
+                Action::RotateRight,
+                vec![
+                    Binding::Key(KeyCode::KeyE),
+                    Binding::Gamepad(Pad::RightTrigger),
+                ],
+            ),
+            (
+                Action::Restart,
+                vec![Binding::Key(KeyCode::KeyR), Binding::Gamepad(Pad::Select)],
+            ),
+            (
+                Action::Confirm,
+                vec![Binding::Key(KeyCode::Enter), Binding::Gamepad(Pad::South)],
+            ),
+            (
+                Action::Back,
+                vec![Binding::Key(KeyCode::Escape), Binding::Gamepad(Pad::East)],
+            ),
+            (
+                Action::FocusPrevious,
+                vec![
+                    Binding::Key(KeyCode::ArrowUp),
+                    Binding::Gamepad(Pad::DPadUp),
+                    Binding::Gamepad(Pad::DPadLeft),
+                ],
+            ),
+            (
+                Action::FocusNext,
+                vec![
+                    Binding::Key(KeyCode::ArrowDown),
+                    Binding::Gamepad(Pad::DPadDown),
+                    Binding::Gamepad(Pad::DPadRight),
+                ],
+            ),
+        ]))
+    }
+}
+
+//Where an action came from, gamepads are told apart so each can drive its own cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputDevice {
+    KeyboardMouse,
+    Touch,
+    Gamepad(Gamepad),
+}
+
+//Actions held, pressed and released this frame, per device and across all of them
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pub(super) devices: HashMap<InputDevice, ButtonInput<Action>>,
+    pub(super) combined: ButtonInput<Action>,
+    //Mouse wheel lines scrolled this frame, up positive
+    pub wheel: f32,
+    //Radians two fingers twisted this frame, counter-clockwise positive
+    pub twist: f32,
+}
+
+impl ActionState {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.combined.just_pressed(action)
+    }
+
+    pub fn device(&self, device: InputDevice) -> Option<&ButtonInput<Action>> {
+        self.devices.get(&device)
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{mode, score};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, SystemTime},
+};
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+//Bump when the stored layout changes and teach `Leaderboards::upgrade` to read the old one
+const LEADERBOARD_VERSION: u32 = 1;
+const LEADERBOARD_KEY: &str = "leaderboard";
+//Runs kept per mode
+pub const LEADERBOARD_SIZE: usize = 10;
+pub const NAME_LENGTH: usize = 12;
+const DEFAULT_NAME: &str = "Player";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: u64,
+    //Seconds since the unix epoch
+    pub date: u64,
+    pub duration: f32,
+    pub seed: u64,
+    #[serde(default)]
+    pub modifiers: score::RunModifiers,
+}
+
+impl LeaderboardEntry {
+    pub fn new(name: &str, summary: &score::RunSummary) -> Self {
+        Self {
+            name: name.to_string(),
+            score: summary.stats.score,
+            date: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+            duration: summary.duration,
+            seed: summary.seed,
+            modifiers: summary.modifiers,
+        }
+    }
+
+    //The entry's day as year-month-day
+    pub fn date_label(&self) -> String {
+        //Days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+        let days = (self.date / 86400) as i64 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        format!("{}-{:02}-{:02}", year, month, day)
+    }
+}
+
+//Only the version of the stored board, read first to know which layout the rest is in
+#[derive(Deserialize)]
+struct StoredVersion {
+    version: u32,
+}
+
+//Best runs per mode, saved under "leaderboard"
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Leaderboards {
+    pub version: u32,
+    pub boards: HashMap<mode::GameMode, Vec<LeaderboardEntry>>,
+    //Offered again the next time a run makes the board
+    pub last_name: String,
+}
+
+impl Default for Leaderboards {
+    fn default() -> Self {
+        Self {
+            version: LEADERBOARD_VERSION,
+            boards: HashMap::default(),
+            last_name: DEFAULT_NAME.to_string(),
+        }
+    }
+}
+
+impl Leaderboards {
+    pub fn board(&self, game_mode: mode::GameMode) -> &[LeaderboardEntry] {
+        self.boards
+            .get(&game_mode)
+            .map_or(&[], |board| board.as_slice())
+    }
+
+    pub fn best(&self, game_mode: mode::GameMode) -> u64 {
+        self.board(game_mode).first().map_or(0, |entry| entry.score)
+    }
+
+    pub fn qualifies(&self, game_mode: mode::GameMode, score: u64) -> bool {
+        let board = self.board(game_mode);
+
+        score > 0
+            && (board.len() < LEADERBOARD_SIZE
+                || board.last().is_none_or(|entry| score > entry.score))
+    }
+
+    //Places the entry by score, ties go to the earlier run, returns its rank if it made the board
+    pub fn insert(&mut self, game_mode: mode::GameMode, entry: LeaderboardEntry) -> Option<usize> {
+        let board = self.boards.entry(game_mode).or_default();
+        let rank = board
+            .iter()
+            .position(|ranked| entry.score > ranked.score)
+            .unwrap_or(board.len());
+
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+
+        board.insert(rank, entry);
+        board.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+
+    pub fn rename(&mut self, game_mode: mode::GameMode, rank: usize, name: &str) {
+        if let Some(entry) = self
+            .boards
+            .get_mut(&game_mode)
+            .and_then(|board| board.get_mut(rank))
+        {
+            entry.name = name.to_string();
+        }
+    }
+
+    //Reads the board in the layout it was saved with and brings it up to date one version at a time.
+    //A new version adds an arm that reads the old layout and converts it to the next one.
+    fn upgrade(pkv: &PkvStore, version: u32) -> Option<Self> {
+        let mut leaderboards = match version {
+            LEADERBOARD_VERSION => pkv.get::<Self>(LEADERBOARD_KEY).ok()?,
+            //Saved by a newer build, fields it added are skipped and the entries kept
+            newer if newer > LEADERBOARD_VERSION => {
+                warn!(
+                    "Leaderboard version {} is newer than {}, reading what this build knows",
+                    newer, LEADERBOARD_VERSION
+                );
+                pkv.get::<Self>(LEADERBOARD_KEY).ok()?
+            }
+            _ => return None,
+        };
+
+        leaderboards.version = LEADERBOARD_VERSION;
+        Some(leaderboards)
+    }
+
+    //Boards from before there was a leaderboard start with the old single high score
+    fn migrate(pkv: &PkvStore) -> Self {
+        let mut leaderboards = Self::default();
+
+        for game_mode in mode::GameMode::ALL {
+            let Ok(highscore) = pkv.get::<u64>(game_mode.highscore_key()) else {
+                continue;
+            };
+
+            if highscore > 0 {
+                leaderboards.insert(
+                    game_mode,
+                    LeaderboardEntry {
+                        name: DEFAULT_NAME.to_string(),
+                        score: highscore,
+                        date: 0,
+                        duration: 0.0,
+                        seed: 0,
+                        modifiers: score::RunModifiers::default(),
+                    },
+                );
+            }
+        }
+
+        leaderboards
+    }
+}
+
+pub fn load_leaderboards(pkv: Res<PkvStore>, mut leaderboards: ResMut<Leaderboards>) {
+    *leaderboards = match pkv.get::<StoredVersion>(LEADERBOARD_KEY) {
+        Ok(stored) => Leaderboards::upgrade(&pkv, stored.version).unwrap_or_else(|| {
+            warn!(
+                "Leaderboard version {} can't be read by version {}, starting over from the high scores",
+                stored.version, LEADERBOARD_VERSION
+            );
+            Leaderboards::migrate(&pkv)
+        }),
+        Err(_) => Leaderboards::migrate(&pkv),
+    };
+}
+
+pub fn save_leaderboards(pkv: &mut PkvStore, leaderboards: &Leaderboards) {
+    if let Err(e) = pkv.set(LEADERBOARD_KEY, leaderboards) {
+        error!("Failed to save leaderboard: {}", e);
+    }
+}
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Leaderboards>()
+            .add_systems(Startup, load_leaderboards);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u64, date: u64) -> LeaderboardEntry {
+        LeaderboardEntry {
+            name: DEFAULT_NAME.to_string(),
+            score,
+            date,
+            duration: 0.0,
+            seed: 0,
+            modifiers: score::RunModifiers::default(),
+        }
+    }
+
+    fn full_board() -> Leaderboards {
+        let mut leaderboards = Leaderboards::default();
+        for score in 1..=LEADERBOARD_SIZE as u64 {
+            leaderboards.insert(mode::GameMode::Classic, entry(score * 10, 0));
+        }
+        leaderboards
+    }
+
+    #[test]
+    fn date_label_is_the_utc_day() {
+        assert_eq!(entry(0, 0).date_label(), "1970-01-01");
+        assert_eq!(entry(0, 951782400).date_label(), "2000-02-29");
+        assert_eq!(entry(0, 1709251199).date_label(), "2024-02-29");
+        assert_eq!(entry(0, 1709251200).date_label(), "2024-03-01");
+        assert_eq!(entry(0, 4102444800).date_label(), "2100-01-01");
+    }
+
+    #[test]
+    fn full_board_only_takes_better_runs() {
+        let mut leaderboards = full_board();
+        let board = leaderboards.board(mode::GameMode::Classic);
+        assert_eq!(board.len(), LEADERBOARD_SIZE);
+        assert_eq!(board.first().map(|entry| entry.score), Some(100));
+        assert_eq!(board.last().map(|entry| entry.score), Some(10));
+
+        assert!(!leaderboards.qualifies(mode::GameMode::Classic, 10));
+        assert_eq!(
+            leaderboards.insert(mode::GameMode::Classic, entry(10, 0)),
+            None
+        );
+        assert_eq!(
+            leaderboards.insert(mode::GameMode::Classic, entry(5, 0)),
+            None
+        );
+
+        assert!(leaderboards.qualifies(mode::GameMode::Classic, 11));
+        assert_eq!(
+            leaderboards.insert(mode::GameMode::Classic, entry(11, 0)),
+            Some(LEADERBOARD_SIZE - 1)
+        );
+        let board = leaderboards.board(mode::GameMode::Classic);
+        assert_eq!(board.len(), LEADERBOARD_SIZE);
+        assert_eq!(board.last().map(|entry| entry.score), Some(11));
+    }
+
+    #[test]
+    fn ties_go_to_the_earlier_run() {
+        let mut leaderboards = full_board();
+        assert_eq!(
+            leaderboards.insert(mode::GameMode::Classic, entry(100, 1)),
+            Some(1)
+        );
+        let board = leaderboards.board(mode::GameMode::Classic);
+        assert_eq!(board[0].date, 0);
+        assert_eq!(board[1].date, 1);
+    }
+
+    #[test]
+    fn empty_board_takes_any_score() {
+        let leaderboards = Leaderboards::default();
+        assert!(leaderboards.qualifies(mode::GameMode::Zen, 1));
+        assert!(!leaderboards.qualifies(mode::GameMode::Zen, 0));
+    }
+}
@@ -0,0 +1,137 @@
+use super::types;
+use avian2d::prelude::*;
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadDirectError},
+    prelude::*,
+};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use serde::Deserialize;
+use thiserror::Error;
+
+pub const CATALOG_PATH: &str = "data/trash.catalog.ron";
+
+#[derive(Deserialize)]
+pub struct TrashItem {
+    pub name: String,
+    pub sprite: String,
+    pub category: types::TrashCategory,
+    pub size: Vec2,
+    pub mass: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    pub points: u64,
+    pub time_bonus: f32,
+    pub spawn_weight: f32,
+    //Falls back to the category's material, soda cans are metal even though they go in plastic
+    #[serde(default)]
+    pub material: Option<types::Material>,
+    #[serde(skip)]
+    pub image: Handle<Image>,
+    //Outline of the sprite in world units, traced once when the catalog loads
+    #[serde(skip)]
+    pub hull: Vec<Vec2>,
+}
+
+impl TrashItem {
+    pub fn material(&self) -> types::Material {
+        self.material.unwrap_or(self.category.material())
+    }
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct TrashCatalog {
+    pub items: Vec<TrashItem>,
+}
+
+impl TrashCatalog {
+    //Weighted random item index, items without a bin in the level never come up.
+    //None when no item fits any of the bins.
+    pub fn pick(&self, bins: &[types::TrashCategory], rng: &mut impl Rng) -> Option<usize> {
+        let weights = self.items.iter().map(|item| {
+            if bins.contains(&item.category) {
+                item.spawn_weight
+            } else {
+                0.0
+            }
+        });
+
+        WeightedIndex::new(weights)
+            .ok()
+            .map(|weights| weights.sample(rng))
+    }
+}
+
+#[derive(Resource)]
+pub struct TrashCatalogHandle(pub Handle<TrashCatalog>);
+
+#[derive(Debug, Error)]
+pub enum TrashCatalogLoaderError {
+    #[error("Could not read trash catalog: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse trash catalog: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Trash catalog has no items")]
+    Empty,
+    #[error("Could not load trash sprite: {0}")]
+    Sprite(#[from] LoadDirectError),
+    #[error("Sprite for {0} has no usable outline")]
+    NoOutline(String),
+}
+
+#[derive(Default)]
+pub struct TrashCatalogLoader;
+
+impl AssetLoader for TrashCatalogLoader {
+    type Asset = TrashCatalog;
+    type Settings = ();
+    type Error = TrashCatalogLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut catalog = ron::de::from_bytes::<TrashCatalog>(&bytes)?;
+        if catalog.items.is_empty() {
+            return Err(TrashCatalogLoaderError::Empty);
+        }
+
+        //Sprites become dependencies of the catalog, so they preload with it
+        for item in catalog.items.iter_mut() {
+            item.image = load_context.load(item.sprite.clone());
+
+            //Outlines are traced from the CPU copy of the sprite, so headless runs get colliders too
+            let loaded = load_context
+                .loader()
+                .direct()
+                .load::<Image>(item.sprite.clone())
+                .await?;
+            let image = loaded.get();
+            let scale = image.size().as_vec2() / item.size;
+
+            item.hull = edges::Edges::from(image)
+                .single_image_edge_translated()
+                .iter()
+                .map(|point| Vec2::new(point.x / scale.x, point.y / scale.y))
+                .collect();
+
+            if Collider::convex_hull(item.hull.clone()).is_none() {
+                return Err(TrashCatalogLoaderError::NoOutline(item.name.clone()));
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["catalog.ron"]
+    }
+}
+
+pub fn load_catalog(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TrashCatalogHandle(asset_server.load(CATALOG_PATH)));
+}
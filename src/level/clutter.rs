@@ -0,0 +1,81 @@
+use super::types;
+use crate::{mode, score, ui};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+//Trash slower than this that is lying on something has come to rest
+const RESTING_SPEED: f32 = 2.0;
+
+#[derive(Resource, Clone)]
+pub struct ClutterSettings {
+    //Share of the limit at which the meter starts warning
+    pub warn_at: f32,
+    //Total area of resting trash that also overflows the arena, None only counts items
+    pub max_area: Option<f32>,
+}
+
+impl Default for ClutterSettings {
+    fn default() -> Self {
+        Self {
+            warn_at: 0.75,
+            max_area: None,
+        }
+    }
+}
+
+//Trash lying around the arena right now, on the floor or piled on other trash
+#[derive(Resource, Default)]
+pub struct Clutter {
+    pub items: usize,
+    pub area: f32,
+    //How full the arena is, overflowing past 1
+    pub fill: f32,
+    pub warning: bool,
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_clutter(
+    mut clutter: ResMut<Clutter>,
+    settings: Res<ClutterSettings>,
+    game_mode: Res<mode::GameMode>,
+    trash_query: Query<
+        (&LinearVelocity, &CollidingEntities, &Collider),
+        (With<types::Trash>, Without<types::HeldObject>),
+    >,
+) {
+    let resting = trash_query
+        .iter()
+        .filter(|(velocity, colliding_entities, _)| {
+            velocity.0.length() < RESTING_SPEED && !colliding_entities.0.is_empty()
+        });
+
+    clutter.items = 0;
+    clutter.area = 0.0;
+    for (.., collider) in resting {
+        clutter.items += 1;
+        clutter.area += collider.mass_properties(1.0).mass.0;
+    }
+
+    clutter.fill = match game_mode.clutter_limit() {
+        Some(limit) => {
+            let by_items = clutter.items as f32 / limit.max(1) as f32;
+            let by_area = settings
+                .max_area
+                .map_or(0.0, |max_area| clutter.area / max_area);
+            by_items.max(by_area)
+        }
+        None => 0.0,
+    };
+    clutter.warning = clutter.fill >= settings.warn_at;
+}
+
+//Ends the run once more trash lies around than the mode tolerates
+pub fn check_overflow(
+    clutter: Res<Clutter>,
+    summarizer: score::RunSummarizer,
+    mut game_over_event_writer: EventWriter<ui::types::GameOverEvent>,
+) {
+    if clutter.fill > 1.0 {
+        game_over_event_writer.send(summarizer.game_over(ui::types::GameOverReason::Overflow));
+    }
+}
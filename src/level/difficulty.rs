@@ -0,0 +1,134 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+pub const DIFFICULTY_PATH: &str = "data/difficulty.curve.ron";
+
+//Value that grows (or shrinks) with score and run time, clamped to [min, max]
+#[derive(Deserialize, Clone, Copy)]
+pub struct CurveParameter {
+    pub base: f32,
+    pub per_score: f32,
+    pub per_second: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl CurveParameter {
+    //Clamping needs min <= max and nothing may be NaN
+    fn is_valid(&self) -> bool {
+        [
+            self.base,
+            self.per_score,
+            self.per_second,
+            self.min,
+            self.max,
+        ]
+        .iter()
+        .all(|value| value.is_finite())
+            && self.min <= self.max
+    }
+
+    pub fn evaluate(&self, score: u64, elapsed: f32) -> f32 {
+        (self.base + self.per_score * score as f32 + self.per_second * elapsed)
+            .clamp(self.min, self.max)
+    }
+}
+
+#[derive(Asset, TypePath, Resource, Deserialize, Clone)]
+pub struct DifficultyCurve {
+    pub starting_time: f32,
+    //How fast the clock runs down
+    pub time_drain: CurveParameter,
+    //Seconds between falling trash
+    pub spawn_every: CurveParameter,
+    //Scales each catalog item's time bonus
+    pub time_bonus_scale: CurveParameter,
+    //Seconds lost for using the wrong bin
+    pub wrong_bin_penalty: CurveParameter,
+}
+
+impl DifficultyCurve {
+    //Name of the first parameter that can not be evaluated
+    fn invalid_parameter(&self) -> Option<&'static str> {
+        if !self.starting_time.is_finite() || self.starting_time <= 0.0 {
+            return Some("starting_time");
+        }
+
+        [
+            ("time_drain", &self.time_drain),
+            ("spawn_every", &self.spawn_every),
+            ("time_bonus_scale", &self.time_bonus_scale),
+            ("wrong_bin_penalty", &self.wrong_bin_penalty),
+        ]
+        .into_iter()
+        .find(|(_, parameter)| !parameter.is_valid())
+        .map(|(name, _)| name)
+    }
+}
+
+#[derive(Resource)]
+pub struct DifficultyCurveHandle(pub Handle<DifficultyCurve>);
+
+#[derive(Debug, Error)]
+pub enum DifficultyCurveLoaderError {
+    #[error("Could not read difficulty curve: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse difficulty curve: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Difficulty curve parameter {0} is out of range or has min above max")]
+    InvalidParameter(&'static str),
+}
+
+#[derive(Default)]
+pub struct DifficultyCurveLoader;
+
+impl AssetLoader for DifficultyCurveLoader {
+    type Asset = DifficultyCurve;
+    type Settings = ();
+    type Error = DifficultyCurveLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let curve = ron::de::from_bytes::<DifficultyCurve>(&bytes)?;
+        if let Some(name) = curve.invalid_parameter() {
+            return Err(DifficultyCurveLoaderError::InvalidParameter(name));
+        }
+
+        Ok(curve)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["curve.ron"]
+    }
+}
+
+pub fn load_difficulty_curve(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(DifficultyCurveHandle(asset_server.load(DIFFICULTY_PATH)));
+}
+
+//Copies the config into the resource whenever it (re)loads, the loading screen waits for it
+pub fn apply_difficulty_curve(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<DifficultyCurve>>,
+    handle: Res<DifficultyCurveHandle>,
+    curves: Res<Assets<DifficultyCurve>>,
+) {
+    for event in asset_events.read() {
+        if event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0) {
+            if let Some(curve) = curves.get(&handle.0) {
+                commands.insert_resource(curve.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use super::types;
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use rand::Rng;
+use serde::Deserialize;
+use thiserror::Error;
+
+pub const LEVEL_PATHS: [&str; 2] = [
+    "data/levels/recycling_center.level.ron",
+    "data/levels/backyard.level.ron",
+];
+
+#[derive(Deserialize)]
+pub struct BackgroundLayout {
+    pub sprite: String,
+    pub size: Vec2,
+    pub tint: (f32, f32, f32),
+    #[serde(skip)]
+    pub image: Handle<Image>,
+}
+
+#[derive(Deserialize)]
+pub struct WallLayout {
+    pub position: Vec2,
+    pub size: Vec2,
+    //Trash resting on the floor is no longer mid-throw
+    #[serde(default)]
+    pub floor: bool,
+}
+
+#[derive(Deserialize)]
+pub struct BinShape {
+    pub sprite: String,
+    pub sprite_size: Vec2,
+    //Relative to the bin position
+    pub colliders: Vec<WallLayout>,
+    pub sensor_offset: Vec2,
+    pub sensor_radii: Vec2,
+    #[serde(skip)]
+    pub image: Handle<Image>,
+}
+
+#[derive(Deserialize)]
+pub struct BinLayout {
+    pub position: Vec2,
+    pub category: types::TrashCategory,
+}
+
+#[derive(Deserialize)]
+pub struct SpawnZone {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl SpawnZone {
+    //Random points are drawn between min and max, so min may not be past max on either axis
+    fn is_valid(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite() && self.min.cmple(self.max).all()
+    }
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct LevelLayout {
+    pub name: String,
+    pub background: BackgroundLayout,
+    pub walls: Vec<WallLayout>,
+    pub bin_shape: BinShape,
+    pub bins: Vec<BinLayout>,
+    //Falling trash spawns here
+    pub drop_zones: Vec<SpawnZone>,
+    //Trash already lying around at the start spawns here
+    pub floor_zones: Vec<SpawnZone>,
+    pub initial_trash: (usize, usize),
+}
+
+impl LevelLayout {
+    //Categories that have a bin in this arena
+    pub fn categories(&self) -> Vec<types::TrashCategory> {
+        self.bins.iter().map(|bin| bin.category).collect()
+    }
+}
+
+//Random point in one of the zones, zones are weighted by their width
+pub fn random_point(zones: &[SpawnZone], rng: &mut impl Rng) -> Vec2 {
+    let total_width: f32 = zones.iter().map(|zone| zone.max.x - zone.min.x).sum();
+    let mut pick = rng.gen_range(0.0..=total_width);
+
+    for zone in zones {
+        let width = zone.max.x - zone.min.x;
+        if pick <= width {
+            return Vec2::new(zone.min.x + pick, rng.gen_range(zone.min.y..=zone.max.y));
+        }
+        pick -= width;
+    }
+
+    Vec2::ZERO
+}
+
+#[derive(Resource)]
+pub struct LevelLayouts(pub Vec<Handle<LevelLayout>>);
+
+#[derive(Resource, Default)]
+pub struct CurrentLevel(pub usize);
+
+impl LevelLayouts {
+    pub fn current(&self, current_level: &CurrentLevel) -> &Handle<LevelLayout> {
+        &self.0[current_level.0 % self.0.len()]
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LevelLayoutLoaderError {
+    #[error("Could not read level layout: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse level layout: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Level layout has no drop or floor zones")]
+    NoSpawnZones,
+    #[error("Spawn zone min {0} is past its max {1}")]
+    InvertedSpawnZone(Vec2, Vec2),
+    #[error("Initial trash range {0}..={1} is inverted")]
+    InvertedInitialTrash(usize, usize),
+}
+
+#[derive(Default)]
+pub struct LevelLayoutLoader;
+
+impl AssetLoader for LevelLayoutLoader {
+    type Asset = LevelLayout;
+    type Settings = ();
+    type Error = LevelLayoutLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut layout = ron::de::from_bytes::<LevelLayout>(&bytes)?;
+        if layout.drop_zones.is_empty() || layout.floor_zones.is_empty() {
+            return Err(LevelLayoutLoaderError::NoSpawnZones);
+        }
+        if let Some(zone) = layout
+            .drop_zones
+            .iter()
+            .chain(&layout.floor_zones)
+            .find(|zone| !zone.is_valid())
+        {
+            return Err(LevelLayoutLoaderError::InvertedSpawnZone(
+                zone.min, zone.max,
+            ));
+        }
+        let (min, max) = layout.initial_trash;
+        if min > max {
+            return Err(LevelLayoutLoaderError::InvertedInitialTrash(min, max));
+        }
+
+        layout.background.image = load_context.load(layout.background.sprite.clone());
+        layout.bin_shape.image = load_context.load(layout.bin_shape.sprite.clone());
+
+        Ok(layout)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+pub fn load_levels(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(LevelLayouts(
+        LEVEL_PATHS
+            .iter()
+            .map(|path| asset_server.load(*path))
+            .collect(),
+    ));
+}
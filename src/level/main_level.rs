@@ -1,18 +1,32 @@
-use super::types;
-use crate::state;
+use super::{catalog, layout, trash_spawner, types};
+use crate::{rng, state};
 use avian2d::prelude::*;
 use bevy::prelude::*;
-use rand::{thread_rng, Rng};
+use rand::Rng;
+
+pub fn spawn_level(
+    mut commands: Commands,
+    levels: Res<layout::LevelLayouts>,
+    current_level: Res<layout::CurrentLevel>,
+    layouts: Res<Assets<layout::LevelLayout>>,
+    catalog_handle: Res<catalog::TrashCatalogHandle>,
+    catalogs: Res<Assets<catalog::TrashCatalog>>,
+    mut game_rng: ResMut<rng::GameRng>,
+) {
+    let Some(level) = layouts.get(levels.current(&current_level)) else {
+        error!("Level layout is not loaded!");
+        return;
+    };
 
-pub fn spawn_level(mut commands: Commands, asset_server: Res<AssetServer>) {
     //Background
+    let (r, g, b) = level.background.tint;
     commands.spawn((
         SpriteBundle {
             transform: Transform::from_xyz(0.0, 0.0, 0.0),
-            texture: asset_server.load("sprites/level/level.png"),
+            texture: level.background.image.clone(),
             sprite: Sprite {
-                custom_size: Some(Vec2::new(10.0 * 17.0, 10.0 * 10.0)),
-                color: Color::srgba(0.8, 0.8, 0.8, 1.0),
+                custom_size: Some(level.background.size),
+                color: Color::srgba(r, g, b, 1.0),
                 ..Default::default()
             },
             ..Default::default()
@@ -20,14 +34,59 @@ pub fn spawn_level(mut commands: Commands, asset_server: Res<AssetServer>) {
         StateScoped(state::GameState::InGame),
     ));
 
+    for bin in level.bins.iter() {
+        spawn_bin(&mut commands, &level.bin_shape, bin);
+    }
+
+    //Outer walls
+    for wall in level.walls.iter() {
+        let mut entity = commands.spawn((
+            TransformBundle {
+                local: Transform::from_translation(wall.position.extend(0.0)),
+                ..Default::default()
+            },
+            StateScoped(state::GameState::InGame),
+        ));
+        entity.insert((
+            RigidBody::Static,
+            Collider::rectangle(wall.size.x, wall.size.y),
+        ));
+
+        if wall.floor {
+            entity.insert((types::Floor, types::SurfaceMaterial(types::Material::Floor)));
+        } else {
+            entity.insert((types::Wall, types::SurfaceMaterial(types::Material::Wall)));
+        }
+    }
+
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        error!("Trash catalog is not loaded!");
+        return;
+    };
+
+    let bins = level.categories();
+    let rng = &mut game_rng.gameplay;
+    let (min, max) = level.initial_trash;
+    for _ in 0..rng.gen_range(min..=max) {
+        let position = layout::random_point(&level.floor_zones, rng);
+        let Some(item) = catalog.pick(&bins, rng) else {
+            warn!("No trash in the catalog goes in this arena's bins, starting without any");
+            break;
+        };
+
+        commands.spawn(trash_spawner::trash_bundle(catalog, item, position));
+    }
+}
+
+fn spawn_bin(commands: &mut Commands, shape: &layout::BinShape, bin: &layout::BinLayout) {
     //Trash can
     commands.spawn((
         SpriteBundle {
-            transform: Transform::from_xyz(0.0, -30.0, 2.0),
-            texture: asset_server.load("sprites/misc/trash_can.png"),
+            transform: Transform::from_translation(bin.position.extend(2.0)),
+            texture: shape.image.clone(),
             sprite: Sprite {
-                custom_size: Some(Vec2::new(109.0 * 0.18, 142.0 * 0.18)),
-                color: Color::srgba(0.8, 0.8, 0.8, 1.0),
+                custom_size: Some(shape.sprite_size),
+                color: bin.category.color(),
                 ..Default::default()
             },
             ..Default::default()
@@ -36,102 +95,34 @@ pub fn spawn_level(mut commands: Commands, asset_server: Res<AssetServer>) {
     ));
 
     //Trash can colliders
-    commands
-        .spawn((
-            TransformBundle {
-                local: Transform::from_xyz(0.0, -38.0, 0.0),
-                ..Default::default()
-            },
-            StateScoped(state::GameState::InGame),
-        ))
-        .insert((RigidBody::Static, Collider::rectangle(109.0 * 0.1, 1.0)));
-
-    commands
-        .spawn((
-            TransformBundle {
-                local: Transform::from_xyz(-6.0, -27.5, 0.0),
-                ..Default::default()
-            },
-            StateScoped(state::GameState::InGame),
-        ))
-        .insert((RigidBody::Static, Collider::rectangle(1.0, 142.0 * 0.13)));
-
-    commands
-        .spawn((
-            TransformBundle {
-                local: Transform::from_xyz(7.0, -27.5, 0.0),
-                ..Default::default()
-            },
-            StateScoped(state::GameState::InGame),
-        ))
-        .insert((RigidBody::Static, Collider::rectangle(1.0, 142.0 * 0.13)));
+    for collider in shape.colliders.iter() {
+        commands
+            .spawn((
+                TransformBundle {
+                    local: Transform::from_translation(
+                        (bin.position + collider.position).extend(0.0),
+                    ),
+                    ..Default::default()
+                },
+                StateScoped(state::GameState::InGame),
+            ))
+            .insert((
+                RigidBody::Static,
+                Collider::rectangle(collider.size.x, collider.size.y),
+                types::TrashCanRim,
+                types::SurfaceMaterial(types::Material::Metal),
+            ));
+    }
 
     //Trash can sensor
     commands.spawn((
         TransformBundle {
-            local: Transform::from_xyz(0.0, -34.0, 0.0),
+            local: Transform::from_translation((bin.position + shape.sensor_offset).extend(0.0)),
             ..Default::default()
         },
-        Collider::ellipse(2.5, 2.0),
+        Collider::ellipse(shape.sensor_radii.x, shape.sensor_radii.y),
         Sensor,
-        types::TrashCanSensor,
+        types::TrashCanSensor(bin.category),
         StateScoped(state::GameState::InGame),
     ));
-
-    //Outer walls
-    commands
-        .spawn((
-            TransformBundle {
-                local: Transform::from_xyz(0.0, -40.0, 0.0),
-                ..Default::default()
-            },
-            StateScoped(state::GameState::InGame),
-        ))
-        .insert((RigidBody::Static, Collider::rectangle(160.0, 2.0)));
-
-    commands
-        .spawn((
-            TransformBundle {
-                local: Transform::from_xyz(-80.0, 2.0, 0.0),
-                ..Default::default()
-            },
-            StateScoped(state::GameState::InGame),
-        ))
-        .insert((RigidBody::Static, Collider::rectangle(2.0, 80.0)));
-
-    commands
-        .spawn((
-            TransformBundle {
-                local: Transform::from_xyz(80.0, 2.0, 0.0),
-                ..Default::default()
-            },
-            StateScoped(state::GameState::InGame),
-        ))
-        .insert((RigidBody::Static, Collider::rectangle(2.0, 80.0)));
-
-    let mut rng = thread_rng();
-    for _ in 0..rng.gen_range(4..8) {
-        let x = loop {
-            let candidate_x = rng.gen_range(-70.0..70.0);
-            if !(-8.0..=9.0).contains(&candidate_x) {
-                break candidate_x;
-            }
-        };
-        let y = -27.0;
-
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(x, y, 1.0),
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(30.0, 30.0)),
-                    ..Default::default()
-                },
-                texture: asset_server
-                    .load(format!("sprites/trash/trash_{}.png", rng.gen_range(1..=9))),
-                ..Default::default()
-            },
-            types::Trash { points: Vec::new() },
-            StateScoped(state::GameState::InGame),
-        ));
-    }
 }
@@ -1,85 +1,129 @@
+pub mod catalog;
+pub mod clutter;
+pub mod difficulty;
+pub mod layout;
 pub mod main_level;
+pub mod throw;
 pub mod timer;
 pub mod trash;
 pub mod trash_can;
 pub mod trash_spawner;
+pub mod trick;
 pub mod types;
 
-use crate::{state, ui::loading};
+use crate::{audio, replay, state, ui::loading};
 use bevy::prelude::*;
 
-pub struct LevelPlugin;
+pub struct LevelPlugin {
+    render: bool,
+}
+
+impl LevelPlugin {
+    //Without rendering the trash outlines are skipped, for headless runs
+    pub fn new(render: bool) -> Self {
+        Self { render }
+    }
+}
+
+impl Default for LevelPlugin {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
-        app.init_gizmo_group::<types::TrashGizmoGroup>()
+        if self.render {
+            app.init_gizmo_group::<types::TrashGizmoGroup>()
+                .add_systems(
+                    Update,
+                    trash::update_trash_border.run_if(in_state(state::PauseState::Running)),
+                );
+        }
+
+        app.init_asset::<catalog::TrashCatalog>()
+            .init_asset_loader::<catalog::TrashCatalogLoader>()
+            .init_asset::<layout::LevelLayout>()
+            .init_asset_loader::<layout::LevelLayoutLoader>()
+            .init_resource::<layout::CurrentLevel>()
+            .init_asset::<difficulty::DifficultyCurve>()
+            .init_asset_loader::<difficulty::DifficultyCurveLoader>()
+            .init_resource::<throw::ThrowSettings>()
+            .init_resource::<types::GrabSettings>()
+            .init_resource::<types::RotateSettings>()
+            .init_resource::<clutter::Clutter>()
+            .init_resource::<clutter::ClutterSettings>()
             .add_event::<types::TrashSpawnEvent>()
+            .add_event::<types::TrashBinnedEvent>()
+            .add_event::<types::TrashThrownEvent>()
             .add_systems(
-                OnEnter(state::GameState::InGame),
+                Startup,
                 (
-                    main_level::spawn_level,
-                    timer::spawn_timer,
-                    |mut loading_data: ResMut<loading::LoadingData>,
-                     asset_server: Res<AssetServer>| {
-                        loading_data
-                            .assets
-                            .push(asset_server.load_untyped("sprites/level/level.png").into());
-                        loading_data.assets.push(
-                            asset_server
-                                .load_untyped("sprites/misc/trash_can.png")
-                                .into(),
-                        );
-
-                        for num in 1..=9 {
-                            loading_data.assets.push(
-                                asset_server
-                                    .load_untyped(format!("sprites/trash/trash_{}.png", num))
-                                    .into(),
-                            );
-                        }
-
-                        loading_data
-                            .assets
-                            .push(asset_server.load_untyped("sounds/game/pickup.ogg").into());
-                        loading_data
-                            .assets
-                            .push(asset_server.load_untyped("sounds/game/letgo.ogg").into());
-                        loading_data
-                            .assets
-                            .push(asset_server.load_untyped("sounds/game/hit.ogg").into());
-                        loading_data
-                            .assets
-                            .push(asset_server.load_untyped("sounds/game/gameover.ogg").into());
-
-                        for num in 1..=4 {
-                            loading_data.assets.push(
-                                asset_server
-                                    .load_untyped(format!("sounds/trash/trashcan_{}.ogg", num))
-                                    .into(),
-                            );
-                        }
-                    },
+                    catalog::load_catalog,
+                    layout::load_levels,
+                    difficulty::load_difficulty_curve,
                 ),
             )
+            .add_systems(Update, difficulty::apply_difficulty_curve)
+            .add_systems(OnEnter(state::GameState::InGame), preload_assets)
+            .add_systems(OnExit(state::PauseState::Running), throw::stop_ghost_cursor)
             .add_systems(
-                Update,
-                (
-                    trash::update_trash_border,
-                    trash::grab_object,
-                    trash::release_object,
-                )
-                    .run_if(in_state(state::PauseState::Running)),
+                OnExit(state::PauseState::Loading),
+                (main_level::spawn_level, timer::spawn_timer),
             )
             .add_systems(
                 FixedUpdate,
                 (
+                    (
+                        throw::sync_ghost_cursors,
+                        throw::move_ghost_cursor,
+                        trash::grab_object,
+                        trash::release_object,
+                        trash::rotate_held_object,
+                    )
+                        .chain()
+                        .after(replay::sample_input),
                     trash::load_trash_collisions,
-                    trash::play_hit_sound,
+                    (trick::track_throw_distance, trick::track_throw_collisions)
+                        .before(trash_can::despawn_trash),
                     trash_can::despawn_trash,
-                    timer::update_timer,
+                    (timer::update_timer, timer::check_time_up).chain(),
+                    (clutter::update_clutter, clutter::check_overflow).chain(),
                     trash_spawner::spawn_trash_event,
                 )
                     .run_if(in_state(state::PauseState::Running)),
             );
     }
 }
+
+fn preload_assets(
+    mut loading_data: ResMut<loading::LoadingData>,
+    asset_server: Res<AssetServer>,
+    catalog_handle: Res<catalog::TrashCatalogHandle>,
+    levels: Res<layout::LevelLayouts>,
+    current_level: Res<layout::CurrentLevel>,
+    difficulty_handle: Res<difficulty::DifficultyCurveHandle>,
+) {
+    loading_data
+        .assets
+        .push(levels.current(&current_level).clone().untyped());
+    loading_data.assets.push(catalog_handle.0.clone().untyped());
+    loading_data
+        .assets
+        .push(difficulty_handle.0.clone().untyped());
+
+    let sounds = [
+        audio::types::SoundId::Pickup,
+        audio::types::SoundId::LetGo,
+        audio::types::SoundId::Hit,
+        audio::types::SoundId::GameOver,
+    ];
+    for sound in sounds
+        .into_iter()
+        .chain((1..=4).map(audio::types::SoundId::TrashCan))
+    {
+        loading_data
+            .assets
+            .push(asset_server.load_untyped(sound.path()).into());
+    }
+}
@@ -0,0 +1,136 @@
+use super::types;
+use crate::{replay, state, ui};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+#[derive(Resource, Clone)]
+pub struct ThrowSettings {
+    //Share of the cursor velocity handed to thrown trash
+    pub strength: f32,
+    //Fastest a throw can leave the cursor, in world units per second
+    pub max_throw_speed: f32,
+    //Ticks of cursor movement averaged into the throw
+    pub velocity_samples: usize,
+    //Share of the distance to the pointer the ghost cursor covers each tick
+    pub cursor_follow: f32,
+    //Fastest the ghost cursor moves, so pointer jumps do not tear the joint apart
+    pub max_cursor_speed: f32,
+}
+
+impl Default for ThrowSettings {
+    fn default() -> Self {
+        Self {
+            strength: 0.8,
+            max_throw_speed: 250.0,
+            velocity_samples: 4,
+            cursor_follow: 0.6,
+            max_cursor_speed: 600.0,
+        }
+    }
+}
+
+//Recent ghost cursor velocities, newest last
+#[derive(Component, Default)]
+pub struct CursorVelocity {
+    samples: VecDeque<Vec2>,
+}
+
+impl CursorVelocity {
+    pub fn average(&self) -> Vec2 {
+        if self.samples.is_empty() {
+            return Vec2::ZERO;
+        }
+
+        self.samples.iter().sum::<Vec2>() / self.samples.len() as f32
+    }
+
+    //Velocity to hand to trash let go of right now
+    pub fn throw(&self, settings: &ThrowSettings) -> Vec2 {
+        (self.average() * settings.strength).clamp_length_max(settings.max_throw_speed)
+    }
+}
+
+//Gives every pointer in this tick a ghost cursor, and removes those whose pointer went away
+#[allow(clippy::type_complexity)]
+pub fn sync_ghost_cursors(
+    mut commands: Commands,
+    tick_input: Res<replay::types::TickInput>,
+    ghost_cursor_query: Query<(Entity, &ui::types::GhostCursor, Has<types::HoldingObject>)>,
+) {
+    for pointer in tick_input.pointers.iter() {
+        if ghost_cursor_query
+            .iter()
+            .any(|(_, ghost_cursor, _)| ghost_cursor.0 == pointer.id)
+        {
+            continue;
+        }
+
+        commands.spawn((
+            TransformBundle {
+                local: Transform::from_translation(pointer.cursor.extend(1.0)),
+                ..Default::default()
+            },
+            RigidBody::Kinematic,
+            CursorVelocity::default(),
+            ui::types::GhostCursor(pointer.id),
+            StateScoped(state::GameState::InGame),
+        ));
+    }
+
+    //Cursors still holding something let go first, see `release_object`
+    for (entity, ghost_cursor, holding) in ghost_cursor_query.iter() {
+        if !holding && tick_input.pointer(ghost_cursor.0).is_none() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+//Drives each kinematic ghost cursor towards its pointer with a velocity instead of teleporting it
+pub fn move_ghost_cursor(
+    time: Res<Time>,
+    settings: Res<ThrowSettings>,
+    tick_input: Res<replay::types::TickInput>,
+    mut ghost_cursor_query: Query<(
+        &ui::types::GhostCursor,
+        &Transform,
+        &mut LinearVelocity,
+        &mut CursorVelocity,
+    )>,
+) {
+    let delta = time.delta_seconds();
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (ghost_cursor, transform, mut velocity, mut cursor_velocity) in
+        ghost_cursor_query.iter_mut()
+    {
+        //A pointer that is gone leaves its cursor where it was
+        let target = tick_input
+            .pointer(ghost_cursor.0)
+            .map_or(transform.translation.xy(), |pointer| pointer.cursor);
+        let offset = target - transform.translation.xy();
+
+        velocity.0 =
+            (offset * settings.cursor_follow / delta).clamp_length_max(settings.max_cursor_speed);
+
+        cursor_velocity.samples.push_back(velocity.0);
+        while cursor_velocity.samples.len() > settings.velocity_samples.max(1) {
+            cursor_velocity.samples.pop_front();
+        }
+    }
+}
+
+//A cursor left moving between runs would fling the next grab
+pub fn stop_ghost_cursor(
+    mut ghost_cursor_query: Query<
+        (&mut LinearVelocity, &mut CursorVelocity),
+        With<ui::types::GhostCursor>,
+    >,
+) {
+    for (mut velocity, mut cursor_velocity) in ghost_cursor_query.iter_mut() {
+        velocity.0 = Vec2::ZERO;
+        cursor_velocity.samples.clear();
+    }
+}
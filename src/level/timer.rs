@@ -1,10 +1,18 @@
-use super::types;
-use crate::{score, state, ui};
+use super::{difficulty, types};
+use crate::{mode, score, state, ui};
 use bevy::prelude::*;
 
-pub fn spawn_timer(mut commands: Commands) {
+pub fn spawn_timer(
+    mut commands: Commands,
+    curve: Res<difficulty::DifficultyCurve>,
+    game_mode: Res<mode::GameMode>,
+) {
     commands.spawn((
-        types::TimeRemaining::default(),
+        types::TimeRemaining {
+            remaining: game_mode.starting_time(curve.starting_time),
+            elapsed: 0.0,
+            last_spawn: 0.0,
+        },
         StateScoped(state::GameState::InGame),
     ));
 }
@@ -12,23 +20,41 @@ pub fn spawn_timer(mut commands: Commands) {
 pub fn update_timer(
     time: Res<Time>,
     mut timer_query: Query<&mut types::TimeRemaining>,
-    mut game_over_event_writer: EventWriter<ui::types::GameOverEvent>,
     mut spawn_every_event_writer: EventWriter<types::TrashSpawnEvent>,
     score: Res<score::PlayerScore>,
+    curve: Res<difficulty::DifficultyCurve>,
+    game_mode: Res<mode::GameMode>,
 ) {
     for mut timer in timer_query.iter_mut() {
-        timer.remaining -= time.delta_seconds() * timer.multiplier;
+        timer.elapsed += time.delta_seconds();
         timer.last_spawn += time.delta_seconds();
 
-        if timer.remaining <= 0.0 {
-            game_over_event_writer.send(ui::types::GameOverEvent {
-                final_score: score.score,
-            });
+        if game_mode.timed() {
+            //Only the classic clock speeds up with the score
+            let drain = if game_mode.time_bonuses() {
+                curve.time_drain.evaluate(score.score, timer.elapsed)
+            } else {
+                1.0
+            };
+            timer.remaining -= time.delta_seconds() * drain;
         }
 
-        if timer.last_spawn >= timer.spawn_every {
+        if timer.last_spawn
+            >= curve.spawn_every.evaluate(score.score, timer.elapsed) * game_mode.spawn_scale()
+        {
             spawn_every_event_writer.send(types::TrashSpawnEvent);
             timer.last_spawn = 0.0;
         }
     }
 }
+
+pub fn check_time_up(
+    game_mode: Res<mode::GameMode>,
+    timer_query: Query<&types::TimeRemaining>,
+    summarizer: score::RunSummarizer,
+    mut game_over_event_writer: EventWriter<ui::types::GameOverEvent>,
+) {
+    if game_mode.timed() && timer_query.iter().any(|timer| timer.remaining <= 0.0) {
+        game_over_event_writer.send(summarizer.game_over(ui::types::GameOverReason::TimeUp));
+    }
+}
@@ -1,36 +1,39 @@
-use super::types;
-use crate::ui;
-use avian2d::prelude::*;
-use bevy::{
-    audio::{PlaybackMode, Volume},
-    color::palettes::basic,
-    prelude::*,
+use super::{catalog, throw, types};
+use crate::{
+    audio,
+    replay::{self, types::PointerId},
+    state, ui,
 };
+use avian2d::prelude::*;
+use bevy::{color::palettes::basic, prelude::*};
 
-#[allow(clippy::type_complexity)]
 pub fn load_trash_collisions(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut types::Trash, &Handle<Image>), Without<Collider>>,
-    images: ResMut<Assets<Image>>,
+    mut query: Query<(Entity, &mut types::Trash), Without<Collider>>,
+    catalog_handle: Res<catalog::TrashCatalogHandle>,
+    catalogs: Res<Assets<catalog::TrashCatalog>>,
 ) {
-    for (entity, mut trash, handle) in query.iter_mut() {
-        if let Some(image) = images.get(handle) {
-            let mut points: Vec<Vec2> = edges::Edges::from(image)
-                .single_image_edge_translated()
-                .iter()
-                .map(|point| Vec2::new(point.x / (512.0 / 30.0), point.y / (512.0 / 30.0)))
-                .collect();
-
-            commands.entity(entity).insert((
-                RigidBody::Dynamic,
-                Collider::convex_hull(points.clone()).unwrap(),
-                Restitution::new(0.25).with_combine_rule(CoefficientCombine::Min),
-                GravityScale(4.0),
-                Friction::new(100.0).with_combine_rule(CoefficientCombine::Min),
-            ));
-
-            trash.points.append(&mut points);
-        }
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        return;
+    };
+
+    for (entity, mut trash) in query.iter_mut() {
+        let item = &catalog.items[trash.item];
+
+        let collider = Collider::convex_hull(item.hull.clone()).unwrap();
+        let area = collider.mass_properties(1.0).mass.0;
+
+        commands.entity(entity).insert((
+            RigidBody::Dynamic,
+            collider,
+            ColliderDensity(item.mass / area),
+            Restitution::new(item.restitution).with_combine_rule(CoefficientCombine::Min),
+            GravityScale(4.0),
+            Friction::new(item.friction).with_combine_rule(CoefficientCombine::Min),
+            types::SurfaceMaterial(item.material()),
+        ));
+
+        trash.points.clone_from(&item.hull);
     }
 }
 
@@ -58,232 +61,182 @@ pub fn update_trash_border(
 pub fn grab_object(
     mut commands: Commands,
     space_query: SpatialQuery,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    mouse_pos_query: Query<
-        (Entity, &Transform),
-        (With<ui::types::GhostCursor>, Without<types::HoldingObject>),
+    tick_input: Res<replay::types::TickInput>,
+    grab_settings: Res<types::GrabSettings>,
+    ghost_cursor_query: Query<(Entity, &ui::types::GhostCursor), Without<types::HoldingObject>>,
+    trash_query: Query<
+        (&Transform, &Position, &Rotation, &Collider),
+        (With<types::Trash>, Without<types::HeldObject>),
     >,
-    is_trash: Query<&types::Trash, Without<types::HeldObject>>,
-    asset_server: Res<AssetServer>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
     first_click_query: Query<&types::FirstClick>,
-    touches: Res<Touches>,
 ) {
-    let mut touch_started = false;
-    for finger in touches.iter() {
-        if touches.just_pressed(finger.id()) {
-            touch_started = true;
-        }
-    }
-
-    if mouse_input.just_pressed(MouseButton::Left) || touch_started {
-        for (entity, transform) in mouse_pos_query.iter() {
-            if let Some(ray_hit_data) = space_query.cast_ray_predicate(
-                transform.translation.xy(),
-                Dir2::X,
-                1.0,
-                true,
-                SpatialQueryFilter::default(),
-                &|entity| is_trash.get(entity).is_ok(),
-            ) {
-                commands
-                    .entity(ray_hit_data.entity)
-                    .insert(types::HeldObject);
-                commands.entity(entity).insert(types::HoldingObject);
-
-                commands.spawn((
-                    types::HeldObjectJoint,
-                    RevoluteJoint::new(entity, ray_hit_data.entity)
-                        .with_local_anchor_2(Vec2::X * 1.0)
-                        .with_compliance(0.001),
-                ));
-
-                commands.spawn(AudioBundle {
-                    source: asset_server.load("sounds/game/pickup.ogg"),
-                    settings: PlaybackSettings {
-                        mode: PlaybackMode::Despawn,
-                        volume: Volume::new(0.5),
-                        ..Default::default()
-                    },
-                });
-
-                if first_click_query.iter().count() == 0 {
-                    commands.spawn(types::FirstClick);
-                }
-                return;
-            }
-
-            if let Some(ray_hit_data) = space_query.cast_ray_predicate(
-                transform.translation.xy(),
-                Dir2::Y,
-                1.0,
-                true,
-                SpatialQueryFilter::default(),
-                &|entity| is_trash.get(entity).is_ok(),
-            ) {
-                commands
-                    .entity(ray_hit_data.entity)
-                    .insert(types::HeldObject);
-                commands.entity(entity).insert(types::HoldingObject);
+    //Trash picked up by another finger this same tick
+    let mut grabbed: Vec<Entity> = Vec::new();
 
-                commands.spawn((
-                    types::HeldObjectJoint,
-                    RevoluteJoint::new(entity, ray_hit_data.entity)
-                        .with_local_anchor_2(Vec2::X * 1.0)
-                        .with_compliance(0.001),
-                ));
-
-                commands.spawn(AudioBundle {
-                    source: asset_server.load("sounds/game/pickup.ogg"),
-                    settings: PlaybackSettings {
-                        mode: PlaybackMode::Despawn,
-                        volume: Volume::new(0.5),
-                        ..Default::default()
-                    },
-                });
-
-                if first_click_query.iter().count() == 0 {
-                    commands.spawn(types::FirstClick);
-                }
-                return;
-            }
-
-            if let Some(ray_hit_data) = space_query.cast_ray_predicate(
-                transform.translation.xy(),
-                -Dir2::X,
-                1.0,
-                true,
-                SpatialQueryFilter::default(),
-                &|entity| is_trash.get(entity).is_ok(),
-            ) {
-                commands
-                    .entity(ray_hit_data.entity)
-                    .insert(types::HeldObject);
-                commands.entity(entity).insert(types::HoldingObject);
+    for (cursor_entity, ghost_cursor) in ghost_cursor_query.iter() {
+        let Some(pointer) = tick_input.pointer(ghost_cursor.0) else {
+            continue;
+        };
 
-                commands.spawn((
-                    types::HeldObjectJoint,
-                    RevoluteJoint::new(entity, ray_hit_data.entity)
-                        .with_local_anchor_2(Vec2::X * 1.0)
-                        .with_compliance(0.001),
-                ));
-
-                commands.spawn(AudioBundle {
-                    source: asset_server.load("sounds/game/pickup.ogg"),
-                    settings: PlaybackSettings {
-                        mode: PlaybackMode::Despawn,
-                        volume: Volume::new(0.5),
-                        ..Default::default()
-                    },
-                });
-
-                if first_click_query.iter().count() == 0 {
-                    commands.spawn(types::FirstClick);
-                }
-                return;
-            }
-
-            if let Some(ray_hit_data) = space_query.cast_ray_predicate(
-                transform.translation.xy(),
-                -Dir2::Y,
-                1.0,
-                true,
-                SpatialQueryFilter::default(),
-                &|entity| is_trash.get(entity).is_ok(),
-            ) {
-                commands
-                    .entity(ray_hit_data.entity)
-                    .insert(types::HeldObject);
-                commands.entity(entity).insert(types::HoldingObject);
-
-                commands.spawn((
-                    types::HeldObjectJoint,
-                    RevoluteJoint::new(entity, ray_hit_data.entity)
-                        .with_local_anchor_2(Vec2::X * 1.0)
-                        .with_compliance(0.001),
-                ));
+        if !pointer.just_pressed {
+            continue;
+        }
 
-                commands.spawn(AudioBundle {
-                    source: asset_server.load("sounds/game/pickup.ogg"),
-                    settings: PlaybackSettings {
-                        mode: PlaybackMode::Despawn,
-                        volume: Volume::new(0.5),
-                        ..Default::default()
-                    },
-                });
+        let point = pointer.cursor;
+        let filter = SpatialQueryFilter::default();
+        let grabbable =
+            |entity: &Entity| trash_query.contains(*entity) && !grabbed.contains(entity);
+
+        //Exact hits first, then anything within the grab radius
+        let mut candidates = space_query.point_intersections(point, filter.clone());
+        candidates.retain(grabbable);
+        if candidates.is_empty() {
+            let radius = match pointer.id {
+                PointerId::Mouse => grab_settings.mouse_radius,
+                PointerId::Touch(_) => grab_settings.touch_radius,
+                PointerId::Gamepad(_) => grab_settings.gamepad_radius,
+            };
+
+            candidates =
+                space_query.shape_intersections(&Collider::circle(radius), point, 0.0, filter);
+            candidates.retain(grabbable);
+        }
 
-                if first_click_query.iter().count() == 0 {
-                    commands.spawn(types::FirstClick);
-                }
-                return;
-            }
+        //Topmost by z, later spawns drawn over earlier ones on ties
+        let Some((trash_entity, (transform, position, rotation, collider))) = candidates
+            .into_iter()
+            .filter_map(|entity| trash_query.get(entity).ok().map(|trash| (entity, trash)))
+            .max_by(|(a_entity, (a, ..)), (b_entity, (b, ..))| {
+                a.translation
+                    .z
+                    .total_cmp(&b.translation.z)
+                    .then(a_entity.cmp(b_entity))
+            })
+        else {
+            continue;
+        };
+
+        //Grabs from the edge when the tolerance radius picked trash the cursor is not over
+        let (grab_point, _) = collider.project_point(*position, *rotation, point, true);
+        let local_anchor = transform
+            .compute_affine()
+            .inverse()
+            .transform_point3(grab_point.extend(transform.translation.z))
+            .xy();
+
+        let joint = commands
+            .spawn((
+                types::HeldObjectJoint,
+                RevoluteJoint::new(cursor_entity, trash_entity)
+                    .with_local_anchor_2(local_anchor)
+                    .with_compliance(0.001),
+                StateScoped(state::GameState::InGame),
+            ))
+            .id();
+
+        commands
+            .entity(trash_entity)
+            .insert(types::HeldObject {
+                queued_turn: 0.0,
+                turning: false,
+            })
+            .remove::<types::ThrowTracker>();
+        commands.entity(cursor_entity).insert(types::HoldingObject {
+            object: trash_entity,
+            joint,
+        });
+        grabbed.push(trash_entity);
+
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Pickup));
+
+        if first_click_query.iter().count() == 0 && grabbed.len() == 1 {
+            commands.spawn(types::FirstClick);
         }
     }
 }
 
+//Each pointer only lets go of its own trash, pointers that are gone let go too
 pub fn release_object(
     mut commands: Commands,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    held_objects_query: Query<Entity, With<types::HeldObject>>,
-    holding_objects_query: Query<Entity, With<types::HoldingObject>>,
-    held_object_joint: Query<Entity, With<types::HeldObjectJoint>>,
-    asset_server: Res<AssetServer>,
-    touches: Res<Touches>,
+    tick_input: Res<replay::types::TickInput>,
+    held_objects_query: Query<&Transform, With<types::HeldObject>>,
+    holding_objects_query: Query<(
+        Entity,
+        &ui::types::GhostCursor,
+        &types::HoldingObject,
+        &throw::CursorVelocity,
+    )>,
+    throw_settings: Res<throw::ThrowSettings>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+    mut thrown_event_writer: EventWriter<types::TrashThrownEvent>,
 ) {
-    let mut touch_stopped = false;
-    for finger in touches.iter() {
-        if touches.just_released(finger.id()) {
-            touch_stopped = true;
+    for (holding_entity, ghost_cursor, holding, cursor_velocity) in holding_objects_query.iter() {
+        let letting_go = match tick_input.pointer(ghost_cursor.0) {
+            Some(pointer) => !pointer.held || pointer.just_released,
+            None => true,
+        };
+
+        if !letting_go {
+            continue;
         }
-    }
 
-    if !mouse_input.pressed(MouseButton::Left) || touch_stopped {
-        for holding_entity in holding_objects_query.iter() {
-            commands
-                .entity(holding_entity)
-                .remove::<types::HoldingObject>();
-            for held_entity in held_objects_query.iter() {
-                commands.entity(held_entity).remove::<types::HeldObject>();
-
-                for object_joint_entity in held_object_joint.iter() {
-                    commands.entity(object_joint_entity).despawn_recursive();
+        commands
+            .entity(holding_entity)
+            .remove::<types::HoldingObject>();
+        commands.entity(holding.joint).despawn_recursive();
 
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("sounds/game/letgo.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
-                }
-            }
+        //The trash may already be gone, binned while still held
+        if let Ok(transform) = held_objects_query.get(holding.object) {
+            commands
+                .entity(holding.object)
+                .remove::<types::HeldObject>()
+                .insert((
+                    types::ThrowTracker::new(transform.translation.xy()),
+                    LinearVelocity(cursor_velocity.throw(&throw_settings)),
+                ));
+            thrown_event_writer.send(types::TrashThrownEvent);
         }
+
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::LetGo));
     }
 }
 
-#[allow(clippy::type_complexity)]
-pub fn play_hit_sound(
-    mut commands: Commands,
-    trash_velocity_query: Query<(&LinearVelocity, &CollidingEntities), With<types::Trash>>,
-    audio_object_query: Query<&types::HitObjectAudio>,
-    asset_server: Res<AssetServer>,
+//Spins held trash around the grab point, counter-clockwise for positive turns
+pub fn rotate_held_object(
+    time: Res<Time>,
+    settings: Res<types::RotateSettings>,
+    tick_input: Res<replay::types::TickInput>,
+    holding_objects_query: Query<(&ui::types::GhostCursor, &types::HoldingObject)>,
+    mut held_objects_query: Query<(&mut types::HeldObject, &mut AngularVelocity)>,
 ) {
-    for (velocity, colliding_entities) in trash_velocity_query.iter() {
-        if colliding_entities.0.iter().count() != 0
-            && (velocity.0.x.abs() + velocity.0.y.abs()) / 2.0 >= 20.0
-            && audio_object_query.iter().count() == 0
-        {
-            commands.spawn((
-                AudioBundle {
-                    source: asset_server.load("sounds/game/hit.ogg"),
-                    settings: PlaybackSettings {
-                        mode: PlaybackMode::Despawn,
-                        volume: Volume::new(0.5),
-                        ..Default::default()
-                    },
-                },
-                types::HitObjectAudio,
-            ));
+    let delta = time.delta_seconds();
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (ghost_cursor, holding) in holding_objects_query.iter() {
+        let (Some(pointer), Ok((mut held, mut angular_velocity))) = (
+            tick_input.pointer(ghost_cursor.0),
+            held_objects_query.get_mut(holding.object),
+        ) else {
+            continue;
+        };
+
+        held.queued_turn += pointer.twist;
+        let max_step = settings.max_speed * delta;
+        let queued_step = held.queued_turn.clamp(-max_step, max_step);
+        held.queued_turn -= queued_step;
+
+        let step = pointer.turn.clamp(-1.0, 1.0) * settings.speed * delta + queued_step;
+
+        if step != 0.0 {
+            angular_velocity.0 = step / delta;
+            held.turning = true;
+        } else if held.turning {
+            //Stop where the player left it instead of spinning on
+            angular_velocity.0 = 0.0;
+            held.turning = false;
         }
     }
 }
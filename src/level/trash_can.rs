@@ -1,45 +1,78 @@
-use super::types;
-use crate::score;
+use super::{catalog, difficulty, trick, types};
+use crate::{audio, mode, rng, score};
 use avian2d::prelude::*;
-use bevy::{
-    audio::{PlaybackMode, Volume},
-    prelude::*,
-};
-use rand::{thread_rng, Rng};
+use bevy::prelude::*;
+use rand::Rng;
 
+#[allow(clippy::too_many_arguments)]
 pub fn despawn_trash(
     mut commands: Commands,
-    mut score: ResMut<score::PlayerScore>,
-    sensor_query: Query<&CollidingEntities, With<types::TrashCanSensor>>,
-    trash_query: Query<&types::Trash>,
-    asset_server: Res<AssetServer>,
+    score: Res<score::PlayerScore>,
+    mut binned_event_writer: EventWriter<types::TrashBinnedEvent>,
+    sensor_query: Query<(&CollidingEntities, &types::TrashCanSensor)>,
+    trash_query: Query<(&types::Trash, Option<&types::ThrowTracker>)>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
     mut timer_query: Query<&mut types::TimeRemaining>,
+    catalog_handle: Res<catalog::TrashCatalogHandle>,
+    catalogs: Res<Assets<catalog::TrashCatalog>>,
+    curve: Res<difficulty::DifficultyCurve>,
+    game_mode: Res<mode::GameMode>,
+    mut game_rng: ResMut<rng::GameRng>,
 ) {
-    for colliding_entities in sensor_query.iter() {
-        let mut rng = thread_rng();
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        return;
+    };
 
+    for (colliding_entities, sensor) in sensor_query.iter() {
         for &entity in colliding_entities.0.iter() {
-            if trash_query.get(entity).is_ok() {
-                commands.entity(entity).despawn_recursive();
-
-                score.score += 1;
-
-                commands.spawn(AudioBundle {
-                    source: asset_server.load(format!(
-                        "sounds/trash/trashcan_{}.ogg",
-                        rng.gen_range(1..=4)
-                    )),
-                    settings: PlaybackSettings {
-                        mode: PlaybackMode::Despawn,
-                        volume: Volume::new(0.7),
-                        ..Default::default()
-                    },
-                });
+            let Ok((trash, tracker)) = trash_query.get(entity) else {
+                continue;
+            };
 
+            commands.entity(entity).despawn_recursive();
+
+            sfx_event_writer.send(
+                audio::types::PlaySfx::new(audio::types::SoundId::TrashCan(
+                    game_rng.cosmetic.gen_range(1..=4),
+                ))
+                .with_pitch_variance(0.05)
+                //Binning always wins over impact thuds sharing the same voices
+                .with_priority(150),
+            );
+
+            let item = &catalog.items[trash.item];
+            let correct = trash.category == sensor.0;
+
+            //Tricks only pay out when the trick shot lands in the right bin
+            let tricks = match tracker {
+                Some(tracker) if correct => trick::Trick::detect(tracker),
+                _ => Vec::new(),
+            };
+            let trick_time = tricks.iter().map(|trick| trick.time_bonus()).sum::<f32>();
+
+            binned_event_writer.send(types::TrashBinnedEvent {
+                item: trash.item,
+                category: sensor.0,
+                correct,
+                points: item.points,
+                tricks,
+                thrown: tracker.is_some(),
+            });
+
+            if !game_mode.time_bonuses() {
+                continue;
+            }
+
+            if correct {
+                for mut timer in timer_query.iter_mut() {
+                    timer.remaining += item.time_bonus
+                        * curve.time_bonus_scale.evaluate(score.score, timer.elapsed)
+                        + trick_time;
+                }
+            } else {
+                //Wrong bin, lose some time on top of the score penalty
                 for mut timer in timer_query.iter_mut() {
-                    timer.remaining += 2.5 * (timer.multiplier * 0.7);
-                    timer.multiplier += 0.05;
-                    timer.spawn_every -= 0.04;
+                    timer.remaining -= curve.wrong_bin_penalty.evaluate(score.score, timer.elapsed);
                 }
             }
         }
@@ -1,47 +1,60 @@
-use super::types;
-use crate::state;
-use bevy::{
-    audio::{PlaybackMode, Volume},
-    prelude::*,
-};
-use rand::{thread_rng, Rng};
+use super::{catalog, layout, types};
+use crate::{audio, rng, state};
+use bevy::prelude::*;
 
+pub fn trash_bundle(catalog: &catalog::TrashCatalog, item: usize, position: Vec2) -> impl Bundle {
+    let trash_item = &catalog.items[item];
+
+    (
+        SpriteBundle {
+            transform: Transform::from_translation(position.extend(1.0)),
+            sprite: Sprite {
+                custom_size: Some(trash_item.size),
+                ..Default::default()
+            },
+            texture: trash_item.image.clone(),
+            ..Default::default()
+        },
+        types::Trash {
+            points: Vec::new(),
+            item,
+            category: trash_item.category,
+        },
+        StateScoped(state::GameState::InGame),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_trash_event(
     mut commands: Commands,
     mut event_reader: EventReader<types::TrashSpawnEvent>,
-    asset_server: Res<AssetServer>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+    catalog_handle: Res<catalog::TrashCatalogHandle>,
+    catalogs: Res<Assets<catalog::TrashCatalog>>,
+    levels: Res<layout::LevelLayouts>,
+    current_level: Res<layout::CurrentLevel>,
+    layouts: Res<Assets<layout::LevelLayout>>,
+    mut game_rng: ResMut<rng::GameRng>,
 ) {
+    let (Some(catalog), Some(level)) = (
+        catalogs.get(&catalog_handle.0),
+        layouts.get(levels.current(&current_level)),
+    ) else {
+        return;
+    };
+
+    let bins = level.categories();
+
     for _ in event_reader.read() {
-        let mut rng = thread_rng();
-        let x = loop {
-            let candidate_x = rng.gen_range(-70.0..70.0);
-            if !(-8.0..=9.0).contains(&candidate_x) {
-                break candidate_x;
-            }
+        let rng = &mut game_rng.gameplay;
+        let position = layout::random_point(&level.drop_zones, rng);
+        let Some(item) = catalog.pick(&bins, rng) else {
+            warn!("No trash in the catalog goes in this arena's bins, skipping the spawn");
+            continue;
         };
 
-        commands.spawn(AudioBundle {
-            source: asset_server.load("sounds/game/spawn.ogg"),
-            settings: PlaybackSettings {
-                mode: PlaybackMode::Despawn,
-                volume: Volume::new(0.75),
-                ..Default::default()
-            },
-        });
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Spawn));
 
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(x, 50.0, 1.0),
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(30.0, 30.0)),
-                    ..Default::default()
-                },
-                texture: asset_server
-                    .load(format!("sprites/trash/trash_{}.png", rng.gen_range(1..=9))),
-                ..Default::default()
-            },
-            types::Trash { points: Vec::new() },
-            StateScoped(state::GameState::InGame),
-        ));
+        commands.spawn(trash_bundle(catalog, item, position));
     }
 }
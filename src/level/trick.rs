@@ -0,0 +1,149 @@
+use super::types;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+//Distance a throw has to cover to count as a long shot, the arena is 160 units wide
+const LONG_SHOT_DISTANCE: f32 = 70.0;
+//Shorter throws than this are just drops and never count as a swish
+const SWISH_MIN_DISTANCE: f32 = 30.0;
+//Bank shots pay out per wall bounce, up to this many
+const MAX_BANKS: u32 = 3;
+//Trash slower than this for `SETTLE_TIME` seconds has come to rest and the throw is over
+const SETTLE_SPEED: f32 = 2.0;
+const SETTLE_TIME: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Trick {
+    LongShot,
+    BankShot(u32),
+    //Banked off the walls on both sides
+    WallToWall,
+    Ricochet,
+    Swish,
+}
+
+impl Trick {
+    //Finds every trick a throw pulled off on its way into the can
+    pub fn detect(tracker: &types::ThrowTracker) -> Vec<Trick> {
+        let mut tricks = Vec::new();
+
+        if tracker.travelled >= LONG_SHOT_DISTANCE {
+            tricks.push(Trick::LongShot);
+        }
+
+        if tracker.wall_hits() > 0 {
+            tricks.push(Trick::BankShot(tracker.wall_hits().min(MAX_BANKS)));
+        }
+
+        if tracker.left_wall_hits > 0 && tracker.right_wall_hits > 0 {
+            tricks.push(Trick::WallToWall);
+        }
+
+        if tracker.trash_hits > 0 {
+            tricks.push(Trick::Ricochet);
+        }
+
+        if tracker.rim_hits == 0 && tracker.travelled >= SWISH_MIN_DISTANCE {
+            tricks.push(Trick::Swish);
+        }
+
+        tricks
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Trick::LongShot => "Long shot!".to_string(),
+            Trick::BankShot(1) => "Bank shot!".to_string(),
+            Trick::BankShot(banks) => format!("{}x Bank shot!", banks),
+            Trick::WallToWall => "Wall to wall!".to_string(),
+            Trick::Ricochet => "Ricochet!".to_string(),
+            Trick::Swish => "Nothing but can!".to_string(),
+        }
+    }
+
+    pub fn points(&self) -> u64 {
+        match self {
+            Trick::LongShot => 3,
+            Trick::BankShot(banks) => 2 * *banks as u64,
+            Trick::WallToWall => 2,
+            Trick::Ricochet => 1,
+            Trick::Swish => 2,
+        }
+    }
+
+    pub fn time_bonus(&self) -> f32 {
+        match self {
+            Trick::LongShot => 1.0,
+            Trick::BankShot(banks) => 0.5 * *banks as f32,
+            Trick::WallToWall => 0.5,
+            Trick::Ricochet => 0.25,
+            Trick::Swish => 0.75,
+        }
+    }
+}
+
+pub fn track_throw_distance(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &Transform,
+        &LinearVelocity,
+        &mut types::ThrowTracker,
+    )>,
+) {
+    for (entity, transform, velocity, mut tracker) in query.iter_mut() {
+        let position = transform.translation.xy();
+        tracker.travelled += position.distance(tracker.last);
+        tracker.last = position;
+
+        if velocity.0.length() < SETTLE_SPEED {
+            tracker.resting += time.delta_seconds();
+        } else {
+            tracker.resting = 0.0;
+        }
+
+        if tracker.resting >= SETTLE_TIME {
+            commands.entity(entity).remove::<types::ThrowTracker>();
+        }
+    }
+}
+
+//Counts what thrown trash bumps into, landing on the floor ends the throw
+#[allow(clippy::type_complexity)]
+pub fn track_throw_collisions(
+    mut commands: Commands,
+    mut collision_event_reader: EventReader<CollisionStarted>,
+    mut tracker_query: Query<&mut types::ThrowTracker>,
+    kind_query: Query<(
+        &Position,
+        Has<types::Wall>,
+        Has<types::Floor>,
+        Has<types::TrashCanRim>,
+        Has<types::Trash>,
+    )>,
+) {
+    for CollisionStarted(a, b) in collision_event_reader.read() {
+        for (thrown, other) in [(*a, *b), (*b, *a)] {
+            let Ok(mut tracker) = tracker_query.get_mut(thrown) else {
+                continue;
+            };
+            let Ok((position, wall, floor, rim, trash)) = kind_query.get(other) else {
+                continue;
+            };
+
+            if floor {
+                commands.entity(thrown).remove::<types::ThrowTracker>();
+            } else if wall && position.x < 0.0 {
+                tracker.left_wall_hits += 1;
+            } else if wall {
+                tracker.right_wall_hits += 1;
+            } else if rim {
+                tracker.rim_hits += 1;
+            } else if trash {
+                tracker.trash_hits += 1;
+            }
+        }
+    }
+}
@@ -1,49 +1,207 @@
 use bevy::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum TrashCategory {
+    Plastic,
+    Paper,
+    Glass,
+    Organic,
+    Landfill,
+}
+
+impl TrashCategory {
+    pub const ALL: [TrashCategory; 5] = [
+        TrashCategory::Plastic,
+        TrashCategory::Paper,
+        TrashCategory::Glass,
+        TrashCategory::Organic,
+        TrashCategory::Landfill,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrashCategory::Plastic => "Plastic",
+            TrashCategory::Paper => "Paper",
+            TrashCategory::Glass => "Glass",
+            TrashCategory::Organic => "Organic",
+            TrashCategory::Landfill => "Landfill",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            TrashCategory::Plastic => Color::srgb(1.0, 0.9, 0.3),
+            TrashCategory::Paper => Color::srgb(0.4, 0.6, 1.0),
+            TrashCategory::Glass => Color::srgb(0.4, 1.0, 0.5),
+            TrashCategory::Organic => Color::srgb(0.8, 0.55, 0.3),
+            TrashCategory::Landfill => Color::srgb(0.85, 0.85, 0.85),
+        }
+    }
+
+    //What trash of this category sounds like when the catalog doesn't say
+    pub fn material(&self) -> Material {
+        match self {
+            TrashCategory::Plastic | TrashCategory::Landfill => Material::Plastic,
+            TrashCategory::Paper => Material::Paper,
+            TrashCategory::Glass => Material::Glass,
+            TrashCategory::Organic => Material::Organic,
+        }
+    }
+}
+
+//What a surface is made of, `assets/data/impacts.sounds.ron` picks the sound when two things hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum Material {
+    Plastic,
+    Metal,
+    Paper,
+    Glass,
+    Organic,
+    Floor,
+    Wall,
+}
+
+#[derive(Component)]
+pub struct SurfaceMaterial(pub Material);
 
 #[derive(Component)]
 pub struct Trash {
     pub points: Vec<Vec2>,
+    pub item: usize,
+    pub category: TrashCategory,
 }
 
 #[derive(Default, Reflect, GizmoConfigGroup)]
 pub struct TrashGizmoGroup;
 
+//How far from the pointer trash can be and still get picked up, in world units
+#[derive(Resource)]
+pub struct GrabSettings {
+    pub mouse_radius: f32,
+    //Fingers cover far more than a mouse pointer
+    pub touch_radius: f32,
+    pub gamepad_radius: f32,
+}
+
+impl Default for GrabSettings {
+    fn default() -> Self {
+        Self {
+            mouse_radius: 0.5,
+            touch_radius: 4.0,
+            gamepad_radius: 3.0,
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct RotateSettings {
+    //Radians per second while a rotate key or shoulder button is held
+    pub speed: f32,
+    //Radians per mouse wheel line
+    pub wheel_step: f32,
+    //Fastest wheel and twist turns are played out, so a flick does not spin the trash loose
+    pub max_speed: f32,
+}
+
+impl Default for RotateSettings {
+    fn default() -> Self {
+        Self {
+            speed: 3.0,
+            wheel_step: 0.25,
+            max_speed: 12.0,
+        }
+    }
+}
+
+//On trash while a ghost cursor holds it
 #[derive(Component)]
-pub struct HeldObject;
+pub struct HeldObject {
+    //Wheel and twist radians still to be turned
+    pub queued_turn: f32,
+    pub turning: bool,
+}
 
+//On a ghost cursor, the trash it holds and the joint doing the holding
 #[derive(Component)]
-pub struct HoldingObject;
+pub struct HoldingObject {
+    pub object: Entity,
+    pub joint: Entity,
+}
 
+//The joint already names the cursor and the trash it connects
 #[derive(Component)]
 pub struct HeldObjectJoint;
 
 #[derive(Component)]
-pub struct TrashCanSensor;
+pub struct TrashCanSensor(pub TrashCategory);
 
+//Solid parts of a trash can, touching these rules out a swish
 #[derive(Component)]
-pub struct FirstClick;
+pub struct TrashCanRim;
 
 #[derive(Component)]
-pub struct HitObjectAudio;
+pub struct Wall;
 
 #[derive(Component)]
-pub struct TimeRemaining {
-    pub remaining: f32,
-    pub multiplier: f32,
-    pub spawn_every: f32,
-    pub last_spawn: f32,
+pub struct Floor;
+
+//Added when trash is let go, follows the throw until it lands or is grabbed again
+#[derive(Component)]
+pub struct ThrowTracker {
+    pub last: Vec2,
+    pub travelled: f32,
+    //Bounces off the walls left and right of the arena's middle
+    pub left_wall_hits: u32,
+    pub right_wall_hits: u32,
+    pub trash_hits: u32,
+    pub rim_hits: u32,
+    pub resting: f32,
 }
 
-impl Default for TimeRemaining {
-    fn default() -> Self {
+impl ThrowTracker {
+    pub fn new(release: Vec2) -> Self {
         Self {
-            remaining: 30.0,
-            multiplier: 1.0,
-            spawn_every: 2.8,
-            last_spawn: 0.0,
+            last: release,
+            travelled: 0.0,
+            left_wall_hits: 0,
+            right_wall_hits: 0,
+            trash_hits: 0,
+            rim_hits: 0,
+            resting: 0.0,
         }
     }
+
+    pub fn wall_hits(&self) -> u32 {
+        self.left_wall_hits + self.right_wall_hits
+    }
+}
+
+#[derive(Component)]
+pub struct FirstClick;
+
+#[derive(Component)]
+pub struct TimeRemaining {
+    pub remaining: f32,
+    pub elapsed: f32,
+    pub last_spawn: f32,
 }
 
 #[derive(Event)]
 pub struct TrashSpawnEvent;
+
+//Sent whenever trash lands in a bin, scoring is handled in `score`
+#[derive(Event)]
+pub struct TrashBinnedEvent {
+    pub item: usize,
+    pub category: TrashCategory,
+    pub correct: bool,
+    pub points: u64,
+    pub tricks: Vec<super::trick::Trick>,
+    //Landed in the bin after a throw rather than dropped in while held
+    pub thrown: bool,
+}
+
+//Held trash was let go of
+#[derive(Event)]
+pub struct TrashThrownEvent;
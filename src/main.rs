@@ -1,11 +1,22 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod achievements;
 mod asset_embedding;
+mod audio;
 mod constants;
+mod headless;
+mod input;
+mod leaderboard;
 mod level;
+mod mode;
 mod rendering;
+mod replay;
+mod restart;
+mod rng;
 mod score;
+mod settings;
 mod state;
+mod stats;
 mod ui;
 
 use avian2d::prelude::*;
@@ -16,8 +27,6 @@ use bevy::{
     prelude::*,
     window::WindowResolution,
 };
-#[cfg(not(target_arch = "wasm32"))]
-use bevy::window::WindowMode;
 use bevy_pkv::PkvStore;
 use winit::window::Icon;
 
@@ -30,6 +39,14 @@ fn set_window_icon(windows: NonSend<bevy::winit::WinitWindows>) {
 }
 
 fn main() -> AppExit {
+    if headless::requested() {
+        return headless::run();
+    }
+
+    //Read before the window exists, so even the splash screen opens in the saved window mode
+    let pkv = PkvStore::new("DimGames", "(Re)cycle");
+    let settings = settings::Settings::load(&pkv);
+
     App::new()
         .add_plugins((
             DefaultPlugins
@@ -42,11 +59,8 @@ fn main() -> AppExit {
                             constants::SCREEN_WIDTH,
                             constants::SCREEN_HEIGHT,
                         ),
-                        #[cfg(debug_assertions)]
-                        mode: WindowMode::Windowed,
-                        #[cfg(not(debug_assertions))]
                         #[cfg(not(target_arch = "wasm32"))]
-                        mode: WindowMode::Fullscreen,
+                        mode: settings.window_mode(),
                         prevent_default_event_handling: false,
                         ..Default::default()
                     }),
@@ -72,14 +86,25 @@ fn main() -> AppExit {
                 },
             },
         ))
-        .insert_resource(PkvStore::new("DimGames", "(Re)cycle"))
+        .insert_resource(pkv)
+        .insert_resource(settings)
         .insert_resource(Msaa::Off)
         .add_plugins((
             rendering::PixelPerfectPlugin,
             state::StatePlugin,
+            input::ActionsPlugin,
             ui::ReCycleUiPlugin::default(),
-            level::LevelPlugin,
+            level::LevelPlugin::default(),
+            mode::ModePlugin,
+            leaderboard::LeaderboardPlugin,
+            stats::StatsPlugin,
+            achievements::AchievementsPlugin,
             score::ScorePlugin,
+            rng::RngPlugin,
+            replay::ReplayPlugin::default(),
+            restart::RestartPlugin,
+            settings::SettingsPlugin,
+            audio::AudioManagerPlugin,
         ))
         .add_systems(Startup, set_window_icon)
         .run()
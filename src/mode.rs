@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+//Seconds on the clock in time attack, nothing adds or takes time
+pub const TIME_ATTACK_SECONDS: f32 = 60.0;
+//Zen trash falls this many times slower than the difficulty curve says
+const ZEN_SPAWN_SCALE: f32 = 2.0;
+//Trash lying around the arena before it overflows and the run ends
+const CLUTTER_LIMIT: usize = 20;
+//Survival has no clock, so the floor fills up sooner
+const SURVIVAL_CLUTTER_LIMIT: usize = 12;
+
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameMode {
+    //Race the clock, binning trash buys more time
+    #[default]
+    Classic,
+    //No clock and no game over
+    Zen,
+    //A flat minute, no time bonuses
+    TimeAttack,
+    //No clock, the run ends as soon as trash piles up
+    Survival,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Classic,
+        GameMode::Zen,
+        GameMode::TimeAttack,
+        GameMode::Survival,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::Zen => "Zen",
+            GameMode::TimeAttack => "Time Attack",
+            GameMode::Survival => "Survival",
+        }
+    }
+
+    pub fn next(&self) -> GameMode {
+        let index = GameMode::ALL
+            .iter()
+            .position(|mode| mode == self)
+            .unwrap_or(0);
+        GameMode::ALL[(index + 1) % GameMode::ALL.len()]
+    }
+
+    //Where single high scores were saved before the leaderboard, only read to carry them over
+    pub fn highscore_key(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "highscore",
+            GameMode::Zen => "highscore_zen",
+            GameMode::TimeAttack => "highscore_time_attack",
+            GameMode::Survival => "highscore_survival",
+        }
+    }
+
+    //Whether a clock runs down and ends the run
+    pub fn timed(&self) -> bool {
+        matches!(self, GameMode::Classic | GameMode::TimeAttack)
+    }
+
+    //Whether binning trash adds time and wrong bins take it away
+    pub fn time_bonuses(&self) -> bool {
+        matches!(self, GameMode::Classic)
+    }
+
+    pub fn starting_time(&self, curve_starting_time: f32) -> f32 {
+        match self {
+            GameMode::TimeAttack => TIME_ATTACK_SECONDS,
+            _ => curve_starting_time,
+        }
+    }
+
+    pub fn spawn_scale(&self) -> f32 {
+        match self {
+            GameMode::Zen => ZEN_SPAWN_SCALE,
+            _ => 1.0,
+        }
+    }
+
+    //Resting trash the arena holds before overflowing, zen never overflows
+    pub fn clutter_limit(&self) -> Option<usize> {
+        match self {
+            GameMode::Classic | GameMode::TimeAttack => Some(CLUTTER_LIMIT),
+            GameMode::Zen => None,
+            GameMode::Survival => Some(SURVIVAL_CLUTTER_LIMIT),
+        }
+    }
+}
+
+impl std::str::FromStr for GameMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "classic" => Ok(GameMode::Classic),
+            "zen" => Ok(GameMode::Zen),
+            "time-attack" | "timeattack" => Ok(GameMode::TimeAttack),
+            "survival" => Ok(GameMode::Survival),
+            _ => Err(format!("Unknown game mode {}", s)),
+        }
+    }
+}
+
+//Reads `--mode <classic|zen|time-attack|survival>` from the command line
+fn mode_from_args() -> Option<GameMode> {
+    let mut args = std::env::args();
+
+    while let Some(arg) = args.next() {
+        if arg == "--mode" {
+            return args.next().and_then(|mode| mode.parse().ok());
+        }
+    }
+
+    None
+}
+
+pub struct ModePlugin;
+
+impl Plugin for ModePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(mode_from_args().unwrap_or_default());
+    }
+}
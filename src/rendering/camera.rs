@@ -1,4 +1,5 @@
 use super::types;
+use crate::{level, settings};
 use bevy::{
     prelude::*,
     render::{
@@ -10,6 +11,12 @@ use bevy::{
     window::WindowResized,
 };
 
+//Canvas pixels the picture moves at full trauma and full intensity
+const MAX_SHAKE: f32 = 3.0;
+//Trauma lost per second
+const SHAKE_DECAY: f32 = 2.0;
+const MISSORT_TRAUMA: f32 = 0.5;
+
 pub fn setup_camera(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
     let canvas_size = Extent3d {
         width: types::RES_WIDTH,
@@ -77,3 +84,34 @@ pub fn fit_canvas(
         projection.scale = 1. / h_scale.min(v_scale).round();
     }
 }
+
+//Moves the upscaled canvas rather than the camera, so the pixel grid itself stays intact
+pub fn shake_canvas(
+    time: Res<Time>,
+    settings: Res<settings::Settings>,
+    mut shake: ResMut<types::ScreenShake>,
+    mut canvas_query: Query<&mut Transform, With<types::Canvas>>,
+) {
+    shake.trauma = (shake.trauma - SHAKE_DECAY * time.delta_seconds()).max(0.0);
+
+    //Not random on purpose, the game rng has to stay untouched for replays
+    let strength = shake.trauma * shake.trauma * settings.screen_shake * MAX_SHAKE;
+    let t = time.elapsed_seconds();
+    let offset = Vec2::new((t * 61.0).sin(), (t * 47.0).cos()) * strength;
+
+    for mut transform in canvas_query.iter_mut() {
+        transform.translation.x = offset.x;
+        transform.translation.y = offset.y;
+    }
+}
+
+pub fn shake_on_missort(
+    mut binned_event_reader: EventReader<level::types::TrashBinnedEvent>,
+    mut shake: ResMut<types::ScreenShake>,
+) {
+    for event in binned_event_reader.read() {
+        if !event.correct {
+            shake.add(MISSORT_TRAUMA);
+        }
+    }
+}
@@ -1,13 +1,22 @@
 pub mod camera;
 pub mod types;
 
+use crate::state;
 use bevy::prelude::*;
 
 pub struct PixelPerfectPlugin;
 
 impl Plugin for PixelPerfectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, camera::setup_camera)
-            .add_systems(Update, camera::fit_canvas);
+        app.init_resource::<types::ScreenShake>()
+            .add_systems(Startup, camera::setup_camera)
+            .add_systems(
+                Update,
+                (
+                    camera::fit_canvas,
+                    camera::shake_on_missort.run_if(in_state(state::PauseState::Running)),
+                    camera::shake_canvas,
+                ),
+            );
     }
 }
@@ -17,3 +17,15 @@ pub struct InGameCamera;
 //Normal camera (renders pixel perfect one)
 #[derive(Component)]
 pub struct OuterCamera;
+
+//From 0 to 1 and decaying over time, the canvas moves with its square so small bumps stay subtle
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    pub trauma: f32,
+}
+
+impl ScreenShake {
+    pub fn add(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+}
@@ -0,0 +1,301 @@
+pub mod types;
+
+use crate::{input, level, mode, rng, score, state, ui};
+use bevy::prelude::*;
+
+pub struct ReplayPlugin {
+    save_recordings: bool,
+}
+
+impl ReplayPlugin {
+    pub fn new(save_recordings: bool) -> Self {
+        Self { save_recordings }
+    }
+}
+
+//Runs are only written to disk when launched with `--record`
+impl Default for ReplayPlugin {
+    fn default() -> Self {
+        Self::new(std::env::args().any(|arg| arg == "--record"))
+    }
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.save_recordings {
+            app.add_systems(
+                OnEnter(state::PauseState::GameOver),
+                save_recording.after(finish_run),
+            );
+        }
+
+        app.init_resource::<types::TickInput>()
+            .init_resource::<types::PendingInput>()
+            .insert_resource(types::ReplayMode::Recording(types::Recording::default()))
+            .add_systems(Startup, load_replay_from_args)
+            .add_systems(OnExit(state::PauseState::Loading), start_run)
+            .add_systems(
+                OnEnter(state::PauseState::GameOver),
+                finish_run.before(ui::game_over::spawn_game_over_screen),
+            )
+            .add_systems(
+                Update,
+                (
+                    accumulate_input.run_if(not(is_playback)),
+                    update_replay_cursor.run_if(is_playback),
+                )
+                    .run_if(in_state(state::PauseState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                sample_input.run_if(in_state(state::PauseState::Running)),
+            );
+    }
+}
+
+pub fn is_playback(replay_mode: Res<types::ReplayMode>) -> bool {
+    replay_mode.is_playback()
+}
+
+//Reads `--replay <path>` from the command line and plays that recording instead of the mouse
+pub fn load_replay_from_args(
+    mut replay_mode: ResMut<types::ReplayMode>,
+    mut seed_selection: ResMut<rng::SeedSelection>,
+    mut current_level: ResMut<level::layout::CurrentLevel>,
+    mut game_mode: ResMut<mode::GameMode>,
+) {
+    let mut args = std::env::args();
+    let Some(path) = args
+        .position(|arg| arg == "--replay")
+        .and_then(|_| args.next())
+    else {
+        return;
+    };
+
+    let recording = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| ron::from_str::<types::Recording>(&text).map_err(|e| e.to_string()))
+    {
+        Ok(recording) => recording,
+        Err(e) => {
+            error!("Failed to load replay {}: {}", path, e);
+            return;
+        }
+    };
+
+    if recording.version != types::RECORDING_VERSION {
+        error!(
+            "Failed to load replay {}: recorded by format version {}, this build plays version {}",
+            path,
+            recording.version,
+            types::RECORDING_VERSION
+        );
+        return;
+    }
+
+    info!("Playing back {} ({} ticks)", path, recording.ticks.len());
+
+    seed_selection.0 = Some(recording.seed);
+    current_level.0 = recording.level;
+    *game_mode = recording.mode;
+    *replay_mode = types::ReplayMode::Playback { recording, tick: 0 };
+}
+
+pub fn start_run(
+    mut replay_mode: ResMut<types::ReplayMode>,
+    mut pending_input: ResMut<types::PendingInput>,
+    mut tick_input: ResMut<types::TickInput>,
+    game_rng: Res<rng::GameRng>,
+    current_level: Res<level::layout::CurrentLevel>,
+    game_mode: Res<mode::GameMode>,
+) {
+    *pending_input = types::PendingInput::default();
+    *tick_input = types::TickInput::default();
+
+    match replay_mode.as_mut() {
+        types::ReplayMode::Recording(recording) => {
+            *recording = types::Recording {
+                seed: game_rng.seed(),
+                level: current_level.0,
+                mode: *game_mode,
+                ..Default::default()
+            };
+        }
+        types::ReplayMode::Playback { tick, .. } => *tick = 0,
+    }
+}
+
+pub fn accumulate_input(
+    mut pending_input: ResMut<types::PendingInput>,
+    action_state: Res<input::types::ActionState>,
+    rotate_settings: Res<level::types::RotateSettings>,
+    touches: Res<Touches>,
+) {
+    if let (Some(mouse), Some(actions)) = (
+        pending_input.pointers.get_mut(&types::PointerId::Mouse),
+        action_state.device(input::types::InputDevice::KeyboardMouse),
+    ) {
+        pointer_actions(mouse, actions);
+        mouse.twist += action_state.wheel * rotate_settings.wheel_step;
+    }
+
+    //Each finger is its own pointer for as long as it touches the screen
+    for finger in touches.iter_just_pressed() {
+        let pointer = pending_input.pointer(types::PointerId::Touch(finger.id()));
+        pointer.held = true;
+        pointer.pressed = true;
+    }
+
+    for finger in touches
+        .iter_just_released()
+        .chain(touches.iter_just_canceled())
+    {
+        let pointer = pending_input.pointer(types::PointerId::Touch(finger.id()));
+        pointer.held = false;
+        pointer.released = true;
+    }
+
+    //Twisting two fingers turns whatever either of them holds
+    if action_state.twist != 0.0 {
+        for (_, pointer) in pending_input
+            .pointers
+            .iter_mut()
+            .filter(|(id, pointer)| id.is_touch() && pointer.held)
+        {
+            pointer.twist += action_state.twist;
+        }
+    }
+}
+
+//Grab holds on for as long as it is down, Release lets go early, the rotate actions turn what is held
+pub fn pointer_actions(
+    pointer: &mut types::PendingPointer,
+    actions: &ButtonInput<input::types::Action>,
+) {
+    use input::types::Action;
+
+    let turn = |action, direction: f32| {
+        if actions.pressed(action) {
+            direction
+        } else {
+            0.0
+        }
+    };
+    pointer.turn = turn(Action::RotateLeft, 1.0) + turn(Action::RotateRight, -1.0);
+
+    pointer.held = actions.pressed(Action::Grab) && !actions.pressed(Action::Release);
+    pointer.pressed |= actions.just_pressed(Action::Grab) && !actions.pressed(Action::Release);
+    pointer.released |=
+        actions.just_released(Action::Grab) || actions.just_pressed(Action::Release);
+}
+
+pub fn sample_input(
+    mut replay_mode: ResMut<types::ReplayMode>,
+    mut pending_input: ResMut<types::PendingInput>,
+    mut tick_input: ResMut<types::TickInput>,
+) {
+    match replay_mode.as_mut() {
+        types::ReplayMode::Recording(recording) => {
+            tick_input.pointers = pending_input
+                .pointers
+                .iter()
+                .map(|(id, pointer)| types::PointerInput {
+                    id: *id,
+                    cursor: pointer.cursor,
+                    held: pointer.held,
+                    just_pressed: pointer.pressed,
+                    just_released: pointer.released,
+                    turn: pointer.turn,
+                    twist: pointer.twist,
+                })
+                .collect();
+
+            //Lifted fingers are gone once their release has been seen
+            pending_input
+                .pointers
+                .retain(|id, pointer| !id.is_touch() || pointer.held);
+            for pointer in pending_input.pointers.values_mut() {
+                pointer.pressed = false;
+                pointer.released = false;
+                pointer.twist = 0.0;
+            }
+
+            recording.ticks.push(tick_input.clone());
+        }
+        types::ReplayMode::Playback { recording, tick } => {
+            //Past the end of the recording every pointer just lets go
+            *tick_input = recording.ticks.get(*tick).cloned().unwrap_or_default();
+            *tick += 1;
+        }
+    }
+}
+
+//Moves the on-screen cursor to wherever the replay is pointing
+pub fn update_replay_cursor(
+    camera_query: Query<(&Camera, &GlobalTransform), With<crate::rendering::types::OuterCamera>>,
+    mut cursor_query: Query<&mut Style, With<ui::types::Cursor>>,
+    tick_input: Res<types::TickInput>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    let Some(pointer) = tick_input.pointers.first() else {
+        return;
+    };
+
+    let Some(pos) = camera.world_to_viewport(camera_transform, pointer.cursor.extend(0.0)) else {
+        return;
+    };
+
+    for mut style in cursor_query.iter_mut() {
+        style.top = Val::Px(pos.y);
+        style.left = Val::Px((pos.x - 6.0).abs());
+    }
+}
+
+pub fn finish_run(mut replay_mode: ResMut<types::ReplayMode>, score: Res<score::PlayerScore>) {
+    match replay_mode.as_mut() {
+        types::ReplayMode::Recording(recording) => {
+            recording.final_score = Some(score.score);
+        }
+        types::ReplayMode::Playback { recording, .. } => match recording.final_score {
+            Some(expected) if expected != score.score => {
+                warn!(
+                    "Replay diverged, scored {} but the recording scored {}",
+                    score.score, expected
+                );
+            }
+            _ => info!("Replay finished with score {}", score.score),
+        },
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_recording(replay_mode: Res<types::ReplayMode>) {
+    let types::ReplayMode::Recording(recording) = replay_mode.as_ref() else {
+        return;
+    };
+
+    //The time keeps runs with the same seed and score from overwriting each other
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis());
+    let path = format!(
+        "replays/run-{}-{}-{}.replay.ron",
+        recording.seed,
+        recording.final_score.unwrap_or(0),
+        timestamp
+    );
+
+    let result = std::fs::create_dir_all("replays")
+        .map_err(|e| e.to_string())
+        .and_then(|_| ron::to_string(recording).map_err(|e| e.to_string()))
+        .and_then(|text| std::fs::write(&path, text).map_err(|e| e.to_string()));
+
+    match result {
+        Ok(_) => info!("Saved replay to {}", path),
+        Err(e) => error!("Failed to save replay: {}", e),
+    }
+}
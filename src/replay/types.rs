@@ -0,0 +1,110 @@
+use crate::mode;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+//The mouse, one finger on a touch screen or a gamepad's virtual cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PointerId {
+    Mouse,
+    Touch(u64),
+    Gamepad(usize),
+}
+
+impl PointerId {
+    pub fn is_touch(&self) -> bool {
+        matches!(self, PointerId::Touch(_))
+    }
+}
+
+//What one pointer did during a single FixedUpdate tick
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct PointerInput {
+    pub id: PointerId,
+    pub cursor: Vec2,
+    pub held: bool,
+    pub just_pressed: bool,
+    pub just_released: bool,
+    //Held rotation direction, counter-clockwise positive
+    #[serde(default)]
+    pub turn: f32,
+    //Radians of wheel or twist rotation since the last tick
+    #[serde(default)]
+    pub twist: f32,
+}
+
+//Input that drives grabbing for a single FixedUpdate tick, pointers that are gone let go
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct TickInput {
+    pub pointers: Vec<PointerInput>,
+}
+
+impl TickInput {
+    pub fn pointer(&self, id: PointerId) -> Option<&PointerInput> {
+        self.pointers.iter().find(|pointer| pointer.id == id)
+    }
+}
+
+#[derive(Default)]
+pub struct PendingPointer {
+    //Latest position in world space, the pointer's ghost cursor chases it every tick
+    pub cursor: Vec2,
+    pub held: bool,
+    pub pressed: bool,
+    pub released: bool,
+    pub turn: f32,
+    pub twist: f32,
+}
+
+//Presses and releases seen since the last tick, so none are lost between frames and ticks
+#[derive(Resource, Default)]
+pub struct PendingInput {
+    pub pointers: BTreeMap<PointerId, PendingPointer>,
+}
+
+impl PendingInput {
+    pub fn pointer(&mut self, id: PointerId) -> &mut PendingPointer {
+        self.pointers.entry(id).or_default()
+    }
+}
+
+//Bump whenever a change makes old recordings play out differently
+pub const RECORDING_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct Recording {
+    //Recordings from before versioning read as 0
+    #[serde(default)]
+    pub version: u32,
+    pub seed: u64,
+    pub level: usize,
+    #[serde(default)]
+    pub mode: mode::GameMode,
+    pub final_score: Option<u64>,
+    pub ticks: Vec<TickInput>,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            version: RECORDING_VERSION,
+            seed: 0,
+            level: 0,
+            mode: mode::GameMode::default(),
+            final_score: None,
+            ticks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub enum ReplayMode {
+    Recording(Recording),
+    Playback { recording: Recording, tick: usize },
+}
+
+impl ReplayMode {
+    pub fn is_playback(&self) -> bool {
+        matches!(self, ReplayMode::Playback { .. })
+    }
+}
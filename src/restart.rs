@@ -0,0 +1,66 @@
+use crate::{input, rng, state, ui};
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+//Starts the run over in place, without the menu or the loading screen
+#[derive(Event)]
+pub struct RestartEvent;
+
+pub fn restart_hotkey(
+    action_state: Res<input::types::ActionState>,
+    mut restart_event_writer: EventWriter<RestartEvent>,
+) {
+    if action_state.just_pressed(input::types::Action::Restart) {
+        restart_event_writer.send(RestartEvent);
+    }
+}
+
+//Everything the run spawned goes, then the same setup a fresh run gets runs again.
+//Assets are already loaded, so there is nothing to wait for.
+pub fn restart_run(world: &mut World) {
+    let mut run_entities = world.query_filtered::<
+        (Entity, &StateScoped<state::GameState>),
+        Without<ui::loading::LoadingScreen>,
+    >();
+    let entities: Vec<Entity> = run_entities
+        .iter(world)
+        .filter(|(_, scope)| scope.0 == state::GameState::InGame)
+        .map(|(entity, _)| entity)
+        .collect();
+
+    for entity in entities {
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+
+    world.run_system_once(rng::seed_run);
+    if world
+        .try_run_schedule(OnExit(state::PauseState::Loading))
+        .is_err()
+    {
+        error!("Failed to set up the restarted run!");
+    }
+
+    world
+        .resource_mut::<NextState<state::PauseState>>()
+        .set(state::PauseState::Running);
+}
+
+pub struct RestartPlugin;
+
+impl Plugin for RestartPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RestartEvent>().add_systems(
+            Update,
+            (
+                restart_hotkey.run_if(
+                    not(in_state(state::PauseState::Loading))
+                        .and_then(not(resource_exists::<ui::types::NameEntry>)),
+                ),
+                restart_run.run_if(on_event::<RestartEvent>()),
+            )
+                .chain()
+                .run_if(in_state(state::GameState::InGame)),
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use crate::state;
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+//Separate streams so cosmetic randomness (sound variation) never shifts gameplay.
+//ChaCha8 is a fixed algorithm, so a seed plays out the same on every platform and rand version.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    pub gameplay: ChaCha8Rng,
+    pub cosmetic: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            gameplay: ChaCha8Rng::seed_from_u64(seed),
+            cosmetic: ChaCha8Rng::seed_from_u64(seed ^ 0x9E37_79B9_7F4A_7C15),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+//Seed for the next runs, None rolls a new one every run
+#[derive(Resource, Default)]
+pub struct SeedSelection(pub Option<u64>);
+
+//Reads `--seed <number>` from the command line
+fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args();
+
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            return args.next().and_then(|seed| seed.parse().ok());
+        }
+    }
+
+    None
+}
+
+pub fn seed_run(mut commands: Commands, seed_selection: Res<SeedSelection>) {
+    let seed = seed_selection.0.unwrap_or_else(rand::random);
+    info!("Starting run with seed {}", seed);
+
+    commands.insert_resource(GameRng::new(seed));
+}
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SeedSelection(seed_from_args()))
+            .insert_resource(GameRng::new(0))
+            .add_systems(OnEnter(state::GameState::InGame), seed_run);
+    }
+}
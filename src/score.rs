@@ -1,14 +1,221 @@
-use bevy::prelude::*;
+use crate::{level, mode, rng, state, ui};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
 
-#[derive(Resource, Default)]
+//Seconds between correctly binned items for the combo to keep going
+pub const COMBO_WINDOW: f32 = 2.5;
+//Highest points multiplier a combo can reach
+pub const MAX_COMBO_MULTIPLIER: u64 = 5;
+//Every this many correct items in a row pays out a streak bonus
+pub const STREAK_BONUS_EVERY: u64 = 5;
+pub const STREAK_BONUS: u64 = 5;
+//Points lost for a wrong bin
+pub const WRONG_BIN_PENALTY: u64 = 1;
+
+//Where the points of a run came from
+#[derive(Default, Clone, Copy)]
+pub struct ScoreBreakdown {
+    pub base: u64,
+    pub combo: u64,
+    pub streak: u64,
+    pub tricks: u64,
+    pub penalties: u64,
+}
+
+//The throw whose tricks paid out the most
+#[derive(Clone)]
+pub struct BestShot {
+    pub points: u64,
+    pub tricks: Vec<level::trick::Trick>,
+}
+
+#[derive(Resource, Default, Clone)]
 pub struct PlayerScore {
     pub score: u64,
+    pub sorted: u64,
+    pub missorted: u64,
+    //Items binned in a row, each within `COMBO_WINDOW` of the last
+    pub combo: u64,
+    pub combo_remaining: f32,
+    pub best_combo: u64,
+    //Correct items since the last wrong bin
+    pub streak: u64,
+    pub best_streak: u64,
+    pub tricks: u64,
+    pub best_shot: Option<BestShot>,
+    //Correctly binned items per category, indexed like `TrashCategory::ALL`
+    pub binned: [u64; level::types::TrashCategory::ALL.len()],
+    pub breakdown: ScoreBreakdown,
+}
+
+impl PlayerScore {
+    //Share of binned items that went in the right bin
+    pub fn accuracy(&self) -> f32 {
+        let total = self.sorted + self.missorted;
+        if total == 0 {
+            return 0.0;
+        }
+
+        self.sorted as f32 / total as f32
+    }
+
+    pub fn combo_multiplier(&self) -> u64 {
+        self.combo.clamp(1, MAX_COMBO_MULTIPLIER)
+    }
+
+    //Fraction of the combo window left, for the decay bar
+    pub fn combo_fraction(&self) -> f32 {
+        (self.combo_remaining / COMBO_WINDOW).clamp(0.0, 1.0)
+    }
+
+    pub fn bin_correct(&mut self, points: u64) {
+        self.sorted += 1;
+        self.combo += 1;
+        self.combo_remaining = COMBO_WINDOW;
+        self.best_combo = self.best_combo.max(self.combo);
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+
+        let combo_bonus = points * (self.combo_multiplier() - 1);
+        let streak_bonus = if self.streak.is_multiple_of(STREAK_BONUS_EVERY) {
+            STREAK_BONUS
+        } else {
+            0
+        };
+
+        self.breakdown.base += points;
+        self.breakdown.combo += combo_bonus;
+        self.breakdown.streak += streak_bonus;
+        self.score += points + combo_bonus + streak_bonus;
+    }
+
+    pub fn add_tricks(&mut self, tricks: &[level::trick::Trick]) {
+        let points = tricks.iter().map(|trick| trick.points()).sum::<u64>();
+
+        self.tricks += tricks.len() as u64;
+        self.breakdown.tricks += points;
+        self.score += points;
+
+        if points > 0
+            && self
+                .best_shot
+                .as_ref()
+                .is_none_or(|best| points > best.points)
+        {
+            self.best_shot = Some(BestShot {
+                points,
+                tricks: tricks.to_vec(),
+            });
+        }
+    }
+
+    pub fn bin_wrong(&mut self) {
+        let penalty = WRONG_BIN_PENALTY.min(self.score);
+
+        self.missorted += 1;
+        self.combo = 0;
+        self.combo_remaining = 0.0;
+        self.streak = 0;
+        self.breakdown.penalties += penalty;
+        self.score -= penalty;
+    }
+}
+
+pub fn score_binned_trash(
+    mut binned_event_reader: EventReader<level::types::TrashBinnedEvent>,
+    mut score: ResMut<PlayerScore>,
+) {
+    for event in binned_event_reader.read() {
+        if event.correct {
+            score.binned[event.category as usize] += 1;
+            score.bin_correct(event.points);
+            score.add_tricks(&event.tricks);
+        } else {
+            score.bin_wrong();
+        }
+    }
+}
+
+pub fn decay_combo(time: Res<Time>, mut score: ResMut<PlayerScore>) {
+    if score.combo == 0 {
+        return;
+    }
+
+    score.combo_remaining -= time.delta_seconds();
+    if score.combo_remaining <= 0.0 {
+        score.combo = 0;
+        score.combo_remaining = 0.0;
+    }
+}
+
+//How a finished run went, for the game over screen
+#[derive(Clone)]
+pub struct RunSummary {
+    pub mode: mode::GameMode,
+    pub seed: u64,
+    pub duration: f32,
+    pub modifiers: RunModifiers,
+    pub stats: PlayerScore,
+}
+
+//Choices besides the mode that set a run apart on the leaderboard
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunModifiers {
+    pub level: usize,
+    //The seed was picked by hand instead of rolled
+    pub fixed_seed: bool,
+}
+
+//Everything needed to sum up the run in progress
+#[derive(SystemParam)]
+pub struct RunSummarizer<'w, 's> {
+    score: Res<'w, PlayerScore>,
+    game_mode: Res<'w, mode::GameMode>,
+    game_rng: Res<'w, rng::GameRng>,
+    seed_selection: Res<'w, rng::SeedSelection>,
+    current_level: Res<'w, level::layout::CurrentLevel>,
+    timer_query: Query<'w, 's, &'static level::types::TimeRemaining>,
+}
+
+impl RunSummarizer<'_, '_> {
+    pub fn summary(&self) -> RunSummary {
+        RunSummary {
+            mode: *self.game_mode,
+            seed: self.game_rng.seed(),
+            duration: self.timer_query.iter().map(|timer| timer.elapsed).sum(),
+            modifiers: RunModifiers {
+                level: self.current_level.0,
+                fixed_seed: self.seed_selection.0.is_some(),
+            },
+            stats: self.score.clone(),
+        }
+    }
+
+    pub fn game_over(&self, reason: ui::types::GameOverReason) -> ui::types::GameOverEvent {
+        ui::types::GameOverEvent {
+            reason,
+            summary: self.summary(),
+        }
+    }
+}
+
+//Every run starts from zero, whichever way the last one ended
+pub fn reset_score(mut score: ResMut<PlayerScore>) {
+    *score = PlayerScore::default();
 }
 
 pub struct ScorePlugin;
 
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(PlayerScore::default());
+        app.insert_resource(PlayerScore::default())
+            .add_systems(OnExit(state::PauseState::Loading), reset_score)
+            .add_systems(
+                FixedUpdate,
+                (decay_combo, score_binned_trash)
+                    .chain()
+                    .after(level::trash_can::despawn_trash)
+                    .run_if(in_state(state::PauseState::Running)),
+            );
     }
 }
@@ -0,0 +1,182 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_KEY: &str = "settings";
+
+//Player options, saved under "settings".
+//Options missing from an older save start at their default.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    //Menu hovers and clicks
+    pub ui_volume: f32,
+    pub fullscreen: bool,
+    //Multiplier on the cursor sprite size
+    pub cursor_size: f32,
+    //0 turns screen shake off
+    pub screen_shake: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            ui_volume: 1.0,
+            //Release builds used to always start fullscreen, the web build never does
+            fullscreen: !cfg!(debug_assertions) && !cfg!(target_arch = "wasm32"),
+            cursor_size: 1.0,
+            screen_shake: 1.0,
+        }
+    }
+}
+
+//One row on the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    UiVolume,
+    Fullscreen,
+    CursorSize,
+    ScreenShake,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 7] = [
+        Setting::MasterVolume,
+        Setting::MusicVolume,
+        Setting::SfxVolume,
+        Setting::UiVolume,
+        Setting::Fullscreen,
+        Setting::CursorSize,
+        Setting::ScreenShake,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::MasterVolume => "Master volume",
+            Setting::MusicVolume => "Music volume",
+            Setting::SfxVolume => "Sound effects",
+            Setting::UiVolume => "Menu sounds",
+            Setting::Fullscreen => "Window",
+            Setting::CursorSize => "Cursor size",
+            Setting::ScreenShake => "Screen shake",
+        }
+    }
+
+    //Smallest value, largest value and step size of the sliders
+    fn range(&self) -> Option<(f32, f32, f32)> {
+        match self {
+            Setting::MasterVolume
+            | Setting::MusicVolume
+            | Setting::SfxVolume
+            | Setting::UiVolume => Some((0.0, 1.0, 0.1)),
+            Setting::CursorSize => Some((0.5, 2.0, 0.25)),
+            Setting::ScreenShake => Some((0.0, 1.0, 0.25)),
+            Setting::Fullscreen => None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(pkv: &PkvStore) -> Self {
+        pkv.get::<Settings>(SETTINGS_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self, pkv: &mut PkvStore) {
+        if let Err(e) = pkv.set(SETTINGS_KEY, self) {
+            error!("Failed to save settings: {}", e);
+        }
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::Fullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    fn slider(&mut self, setting: Setting) -> Option<&mut f32> {
+        match setting {
+            Setting::MasterVolume => Some(&mut self.master_volume),
+            Setting::MusicVolume => Some(&mut self.music_volume),
+            Setting::SfxVolume => Some(&mut self.sfx_volume),
+            Setting::UiVolume => Some(&mut self.ui_volume),
+            Setting::CursorSize => Some(&mut self.cursor_size),
+            Setting::ScreenShake => Some(&mut self.screen_shake),
+            Setting::Fullscreen => None,
+        }
+    }
+
+    //Moves a slider one step up or down, toggles just flip
+    pub fn step(&mut self, setting: Setting, up: bool) {
+        match setting {
+            Setting::Fullscreen => self.fullscreen = !self.fullscreen,
+            _ => {
+                let (Some((min, max, step)), Some(value)) = (setting.range(), self.slider(setting))
+                else {
+                    return;
+                };
+
+                let steps = ((*value - min) / step).round() + if up { 1.0 } else { -1.0 };
+                *value = (min + steps * step).clamp(min, max);
+            }
+        }
+    }
+
+    pub fn value_label(&self, setting: Setting) -> String {
+        match setting {
+            Setting::MasterVolume => format!("{:.0}%", self.master_volume * 100.0),
+            Setting::MusicVolume => format!("{:.0}%", self.music_volume * 100.0),
+            Setting::SfxVolume => format!("{:.0}%", self.sfx_volume * 100.0),
+            Setting::UiVolume => format!("{:.0}%", self.ui_volume * 100.0),
+            Setting::Fullscreen => {
+                if self.fullscreen {
+                    "Fullscreen".to_string()
+                } else {
+                    "Windowed".to_string()
+                }
+            }
+            Setting::CursorSize => format!("{:.2}x", self.cursor_size),
+            Setting::ScreenShake => format!("{:.0}%", self.screen_shake * 100.0),
+        }
+    }
+}
+
+pub fn save_settings(mut pkv: ResMut<PkvStore>, settings: Res<Settings>) {
+    settings.save(&mut pkv);
+}
+
+pub fn apply_window_mode(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    for mut window in window_query.iter_mut() {
+        if window.mode != settings.window_mode() {
+            window.mode = settings.window_mode();
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        //Normally already loaded in main, before the window opens
+        app.init_resource::<Settings>().add_systems(
+            Update,
+            (save_settings, apply_window_mode).run_if(resource_changed::<Settings>),
+        );
+    }
+}
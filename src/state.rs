@@ -6,6 +6,11 @@ pub enum GameState {
     Splash,
     Menu,
     InGame,
+    Leaderboard,
+    Stats,
+    Achievements,
+    Settings,
+    Controls,
     Credits,
     Quitting,
 }
@@ -17,6 +22,8 @@ pub enum PauseState {
     Loading,
     Running,
     Paused,
+    //Settings opened from the pause menu, the run stays paused underneath
+    Settings,
     GameOver,
 }
 
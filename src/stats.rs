@@ -0,0 +1,162 @@
+use crate::{level, replay, restart, score, state, ui};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+const STATS_KEY: &str = "stats";
+
+//Totals across every run ever played, saved under "stats".
+//Counters missing from an older save start at their default.
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub runs_played: u64,
+    //Seconds spent in runs, pauses not included
+    pub playtime: f64,
+    //Correctly binned trash by catalog item name
+    pub binned: HashMap<String, u64>,
+    pub missorted: u64,
+    pub throws: u64,
+    //Throws that went into a bin, right or wrong
+    pub throws_binned: u64,
+    pub total_score: u64,
+    pub best_score: u64,
+    //Seconds, the longest a single run lasted
+    pub longest_run: f32,
+}
+
+impl LifetimeStats {
+    pub fn average_score(&self) -> f32 {
+        if self.runs_played == 0 {
+            return 0.0;
+        }
+
+        self.total_score as f32 / self.runs_played as f32
+    }
+
+    pub fn total_binned(&self) -> u64 {
+        self.binned.values().sum()
+    }
+
+    //Share of throws that ended up in a bin
+    pub fn throw_accuracy(&self) -> f32 {
+        if self.throws == 0 {
+            return 0.0;
+        }
+
+        self.throws_binned as f32 / self.throws as f32
+    }
+}
+
+pub fn load_stats(pkv: Res<PkvStore>, mut stats: ResMut<LifetimeStats>) {
+    if let Ok(saved) = pkv.get::<LifetimeStats>(STATS_KEY) {
+        *stats = saved;
+    }
+}
+
+pub fn save_stats(mut pkv: ResMut<PkvStore>, stats: Res<LifetimeStats>) {
+    if let Err(e) = pkv.set(STATS_KEY, stats.as_ref()) {
+        error!("Failed to save stats: {}", e);
+    }
+}
+
+pub fn track_playtime(time: Res<Time>, mut stats: ResMut<LifetimeStats>) {
+    stats.playtime += time.delta_seconds_f64();
+}
+
+pub fn track_throws(
+    mut thrown_event_reader: EventReader<level::types::TrashThrownEvent>,
+    mut stats: ResMut<LifetimeStats>,
+) {
+    stats.throws += thrown_event_reader.read().count() as u64;
+}
+
+pub fn track_binned(
+    mut binned_event_reader: EventReader<level::types::TrashBinnedEvent>,
+    mut stats: ResMut<LifetimeStats>,
+    catalog_handle: Res<level::catalog::TrashCatalogHandle>,
+    catalogs: Res<Assets<level::catalog::TrashCatalog>>,
+) {
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        return;
+    };
+
+    for event in binned_event_reader.read() {
+        if event.thrown {
+            stats.throws_binned += 1;
+        }
+
+        if event.correct {
+            *stats
+                .binned
+                .entry(catalog.items[event.item].name.clone())
+                .or_default() += 1;
+        } else {
+            stats.missorted += 1;
+        }
+    }
+}
+
+pub fn record_run(mut stats: ResMut<LifetimeStats>, summarizer: score::RunSummarizer) {
+    let summary = summarizer.summary();
+
+    stats.runs_played += 1;
+    stats.total_score += summary.stats.score;
+    stats.best_score = stats.best_score.max(summary.stats.score);
+    stats.longest_run = stats.longest_run.max(summary.duration);
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LifetimeStats>()
+            .add_systems(Startup, load_stats)
+            .add_systems(
+                FixedUpdate,
+                (
+                    track_playtime,
+                    track_throws.after(level::trash::release_object),
+                    track_binned.after(level::trash_can::despawn_trash),
+                )
+                    .run_if(
+                        in_state(state::PauseState::Running).and_then(not(replay::is_playback)),
+                    ),
+            )
+            //Saved whenever a run ends, however it ends
+            .add_systems(
+                OnEnter(state::PauseState::GameOver),
+                (record_run.run_if(not(replay::is_playback)), save_stats).chain(),
+            )
+            //Restarting mid-run ends it too, the game over screen already recorded its run
+            .add_systems(
+                Update,
+                (record_run, save_stats)
+                    .chain()
+                    .after(restart::restart_hotkey)
+                    .before(restart::restart_run)
+                    .run_if(
+                        on_event::<restart::RestartEvent>()
+                            .and_then(
+                                in_state(state::PauseState::Running)
+                                    .or_else(in_state(state::PauseState::Paused)),
+                            )
+                            .and_then(not(replay::is_playback)),
+                    ),
+            )
+            .add_systems(OnExit(state::PauseState::Loading), save_stats)
+            //Runs left without a game over, quitting from the pause menu, a run that never loaded does not count
+            .add_systems(
+                OnExit(state::GameState::InGame),
+                (
+                    record_run.run_if(
+                        not(resource_exists::<ui::types::LastRun>)
+                            .and_then(not(resource_exists::<ui::loading::LoadFailed>))
+                            .and_then(not(replay::is_playback)),
+                    ),
+                    save_stats,
+                )
+                    .chain(),
+            );
+    }
+}
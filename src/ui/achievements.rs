@@ -0,0 +1,284 @@
+use bevy::{color::palettes::basic, prelude::*};
+
+use crate::{achievements, audio, input, state};
+
+use super::types;
+
+//Toasts slide in from the right edge, wait, then slide back out
+const TOAST_WIDTH: f32 = 30.0;
+const TOAST_HEIGHT: f32 = 11.0;
+const TOAST_SLIDE: f32 = 0.3;
+const TOAST_SHOWN: f32 = 3.0;
+
+pub struct AchievementsUiPlugin;
+
+impl Plugin for AchievementsUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(state::GameState::Achievements), spawn_achievements)
+            .add_systems(
+                Update,
+                (
+                    update_achievements.run_if(in_state(state::GameState::Achievements)),
+                    (spawn_toasts, slide_toasts).chain(),
+                ),
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct AchievementToast {
+    age: f32,
+    //Row the toast sits in, counted down from the top
+    slot: usize,
+}
+
+pub fn spawn_toasts(
+    mut commands: Commands,
+    mut unlocked_event_reader: EventReader<achievements::AchievementUnlockedEvent>,
+    toast_query: Query<&AchievementToast>,
+    asset_server: Res<AssetServer>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    let mut taken: Vec<usize> = toast_query.iter().map(|toast| toast.slot).collect();
+
+    for event in unlocked_event_reader.read() {
+        //New toasts take the highest free row, so they never land on one still showing
+        let slot = (0..).find(|slot| !taken.contains(slot)).unwrap_or_default();
+        taken.push(slot);
+
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Percent(2.0 + slot as f32 * (TOAST_HEIGHT + 1.0)),
+                        right: Val::Percent(-TOAST_WIDTH),
+                        width: Val::Percent(TOAST_WIDTH),
+                        height: Val::Percent(TOAST_HEIGHT),
+                        border: UiRect::all(Val::Px(3.0)),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        padding: UiRect::all(Val::Percent(1.0)),
+                        ..Default::default()
+                    },
+                    border_color: BorderColor(Color::from(basic::GREEN)),
+                    border_radius: BorderRadius::all(Val::Px(3.0)),
+                    background_color: BackgroundColor(Color::BLACK),
+                    z_index: ZIndex::Global(4),
+                    ..Default::default()
+                },
+                AchievementToast { age: 0.0, slot },
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_sections([
+                    TextSection::new(
+                        format!("Achievement: {}\n", event.name),
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 18.0,
+                            color: Color::from(basic::LIME),
+                        },
+                    ),
+                    TextSection::new(
+                        event.description.clone(),
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 14.0,
+                            color: Color::WHITE,
+                        },
+                    ),
+                ]));
+            });
+
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+    }
+}
+
+pub fn slide_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toast_query: Query<(Entity, &mut AchievementToast, &mut Style)>,
+) {
+    for (entity, mut toast, mut style) in toast_query.iter_mut() {
+        toast.age += time.delta_seconds();
+
+        let shown = if toast.age < TOAST_SLIDE {
+            toast.age / TOAST_SLIDE
+        } else if toast.age < TOAST_SLIDE + TOAST_SHOWN {
+            1.0
+        } else {
+            1.0 - (toast.age - TOAST_SLIDE - TOAST_SHOWN) / TOAST_SLIDE
+        };
+
+        if shown <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        style.right = Val::Percent(-TOAST_WIDTH + shown * (TOAST_WIDTH + 2.0));
+    }
+}
+
+pub fn spawn_achievements(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    handle: Res<achievements::AchievementListHandle>,
+    lists: Res<Assets<achievements::AchievementList>>,
+    unlocked: Res<achievements::UnlockedAchievements>,
+) {
+    let list = lists
+        .get(&handle.0)
+        .map_or(&[][..], |list| list.achievements.as_slice());
+    let unlocked_count = list
+        .iter()
+        .filter(|achievement| unlocked.0.contains(&achievement.id))
+        .count();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                z_index: ZIndex::Global(2),
+                background_color: BackgroundColor(Color::BLACK),
+                ..Default::default()
+            },
+            StateScoped(state::GameState::Achievements),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Achievements",
+                TextStyle {
+                    font: asset_server.load("ui/fonts/title_font.TTF"),
+                    font_size: 35.0,
+                    color: Color::from(basic::GREEN),
+                },
+            ));
+
+            parent.spawn(TextBundle::from_section(
+                format!("{} of {} unlocked\n", unlocked_count, list.len()),
+                TextStyle {
+                    font: asset_server.load("ui/fonts/font.otf"),
+                    font_size: 15.0,
+                    color: Color::WHITE,
+                },
+            ));
+
+            for achievement in list {
+                let unlocked = unlocked.0.contains(&achievement.id);
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Percent(60.0),
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            achievement.name.clone(),
+                            TextStyle {
+                                font: asset_server.load("ui/fonts/font.otf"),
+                                font_size: 18.0,
+                                color: if unlocked {
+                                    Color::from(basic::LIME)
+                                } else {
+                                    Color::srgb(0.5, 0.5, 0.5)
+                                },
+                            },
+                        ));
+
+                        parent.spawn(TextBundle::from_section(
+                            achievement.description.clone(),
+                            TextStyle {
+                                font: asset_server.load("ui/fonts/font.otf"),
+                                font_size: 15.0,
+                                color: if unlocked {
+                                    Color::WHITE
+                                } else {
+                                    Color::srgb(0.5, 0.5, 0.5)
+                                },
+                            },
+                        ));
+                    });
+            }
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        border_color: BorderColor(Color::from(basic::GREEN)),
+                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                        style: Style {
+                            width: Val::Percent(18.0),
+                            height: Val::Percent(6.5),
+                            border: UiRect::all(Val::Px(5.0)),
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            margin: UiRect::all(Val::Percent(1.0)),
+                            ..Default::default()
+                        },
+                        background_color: BackgroundColor(Color::from(basic::GREEN)),
+                        ..Default::default()
+                    },
+                    types::ReturnToMenuButton(false),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back to menu",
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 20.0,
+                            color: Color::from(basic::LIME),
+                        },
+                    ));
+                });
+        });
+}
+
+pub fn update_achievements(
+    mut commands: Commands,
+    mut return_to_menu_query: Query<(
+        &Interaction,
+        &mut BorderColor,
+        &mut types::ReturnToMenuButton,
+    )>,
+    action_state: Res<input::types::ActionState>,
+    fade_to_black_query: Query<&types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    let mut leave = action_state.just_pressed(input::types::Action::Back);
+
+    for (interaction, mut border_color, mut return_button) in return_to_menu_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                return_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !return_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    return_button.0 = true;
+                }
+            }
+            Interaction::Pressed => leave = true,
+        }
+    }
+
+    if leave && fade_to_black_query.iter().count() == 0 {
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+        commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+    }
+}
@@ -0,0 +1,386 @@
+use bevy::{color::palettes::basic, prelude::*};
+use bevy_pkv::PkvStore;
+
+use crate::{audio, input, state};
+
+use super::types;
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RebindTarget>()
+            .add_systems(OnEnter(state::GameState::Controls), spawn_controls)
+            .add_systems(
+                OnExit(state::GameState::Controls),
+                |mut target: ResMut<RebindTarget>| target.0 = None,
+            )
+            .add_systems(
+                Update,
+                (update_controls, update_binding_text)
+                    .chain()
+                    .run_if(in_state(state::GameState::Controls)),
+            );
+    }
+}
+
+//Action and binding slot waiting for the next key or button press
+#[derive(Resource, Default)]
+pub struct RebindTarget(pub Option<(input::types::Action, usize)>);
+
+pub fn spawn_controls(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                z_index: ZIndex::Global(2),
+                background_color: BackgroundColor(Color::BLACK),
+                ..Default::default()
+            },
+            StateScoped(state::GameState::Controls),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Controls",
+                TextStyle {
+                    font: asset_server.load("ui/fonts/title_font.TTF"),
+                    font_size: 35.0,
+                    color: Color::from(basic::GREEN),
+                },
+            ));
+
+            parent.spawn(TextBundle::from_section(
+                "Pick a slot, then press its new key or button\nTaking another action's key gives it the one replaced\n",
+                TextStyle {
+                    font_size: 15.0,
+                    font: asset_server.load("ui/fonts/font.otf"),
+                    color: Color::WHITE,
+                },
+            ));
+
+            for action in input::types::Action::ALL {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Percent(80.0),
+                            height: Val::Percent(5.5),
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            action.label(),
+                            TextStyle {
+                                font: asset_server.load("ui/fonts/font.otf"),
+                                font_size: 18.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(70.0),
+                                    height: Val::Percent(90.0),
+                                    flex_direction: FlexDirection::Row,
+                                    justify_content: JustifyContent::SpaceBetween,
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                for slot in 0..input::types::BINDING_SLOTS {
+                                    spawn_slot_button(parent, &asset_server, action, slot);
+                                }
+                            });
+                    });
+            }
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(10.0),
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                border_color: BorderColor(Color::from(basic::GREEN)),
+                                border_radius: BorderRadius::all(Val::Px(3.0)),
+                                style: Style {
+                                    width: Val::Percent(18.0),
+                                    height: Val::Percent(70.0),
+                                    border: UiRect::all(Val::Px(5.0)),
+                                    align_items: AlignItems::Center,
+                                    justify_content: JustifyContent::Center,
+                                    margin: UiRect::all(Val::Percent(1.0)),
+                                    ..Default::default()
+                                },
+                                background_color: BackgroundColor(Color::from(basic::GREEN)),
+                                ..Default::default()
+                            },
+                            types::ResetBindingsButton(false),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Reset to defaults",
+                                TextStyle {
+                                    font: asset_server.load("ui/fonts/font.otf"),
+                                    font_size: 20.0,
+                                    color: Color::from(basic::LIME),
+                                },
+                            ));
+                        });
+
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                border_color: BorderColor(Color::from(basic::GREEN)),
+                                border_radius: BorderRadius::all(Val::Px(3.0)),
+                                style: Style {
+                                    width: Val::Percent(18.0),
+                                    height: Val::Percent(70.0),
+                                    border: UiRect::all(Val::Px(5.0)),
+                                    align_items: AlignItems::Center,
+                                    justify_content: JustifyContent::Center,
+                                    margin: UiRect::all(Val::Percent(1.0)),
+                                    ..Default::default()
+                                },
+                                background_color: BackgroundColor(Color::from(basic::GREEN)),
+                                ..Default::default()
+                            },
+                            types::ReturnToMenuButton(false),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Back to menu",
+                                TextStyle {
+                                    font: asset_server.load("ui/fonts/font.otf"),
+                                    font_size: 20.0,
+                                    color: Color::from(basic::LIME),
+                                },
+                            ));
+                        });
+                });
+        });
+}
+
+fn spawn_slot_button(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    action: input::types::Action,
+    slot: usize,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                border_color: BorderColor(Color::from(basic::GREEN)),
+                border_radius: BorderRadius::all(Val::Px(3.0)),
+                style: Style {
+                    width: Val::Percent(32.0),
+                    height: Val::Percent(100.0),
+                    border: UiRect::all(Val::Px(3.0)),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(Color::from(basic::GREEN)),
+                ..Default::default()
+            },
+            types::RebindButton(action, slot, false),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("ui/fonts/font.otf"),
+                        font_size: 15.0,
+                        color: Color::from(basic::LIME),
+                    },
+                ),
+                types::BindingText(action, slot),
+            ));
+        });
+}
+
+//The first key, mouse button or gamepad button pressed this frame
+fn pressed_binding(
+    keys: &ButtonInput<KeyCode>,
+    mouse_buttons: &ButtonInput<MouseButton>,
+    gamepad_buttons: &ButtonInput<GamepadButton>,
+) -> Option<input::types::Binding> {
+    keys.get_just_pressed()
+        .next()
+        .map(|key| input::types::Binding::Key(*key))
+        .or_else(|| {
+            mouse_buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| input::types::Binding::Mouse(*button))
+        })
+        .or_else(|| {
+            gamepad_buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| input::types::Binding::Gamepad(button.button_type))
+        })
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn update_controls(
+    mut commands: Commands,
+    mut rebind_button_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::RebindButton),
+        Changed<Interaction>,
+    >,
+    mut reset_button_query: Query<
+        (
+            &Interaction,
+            &mut BorderColor,
+            &mut types::ResetBindingsButton,
+        ),
+        (Changed<Interaction>, Without<types::RebindButton>),
+    >,
+    mut return_to_menu_query: Query<
+        (
+            &Interaction,
+            &mut BorderColor,
+            &mut types::ReturnToMenuButton,
+        ),
+        (
+            Without<types::RebindButton>,
+            Without<types::ResetBindingsButton>,
+        ),
+    >,
+    mut target: ResMut<RebindTarget>,
+    mut bindings: ResMut<input::types::InputBindings>,
+    mut pkv: ResMut<PkvStore>,
+    action_state: Res<input::types::ActionState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    fade_to_black_query: Query<&types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    //While waiting for a press nothing else on the screen reacts, so the press is not used twice.
+    //Back gives up on the rebind instead of being bound.
+    if let Some((action, slot)) = target.0 {
+        if action_state.just_pressed(input::types::Action::Back) {
+            sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+            target.0 = None;
+        } else if let Some(binding) = pressed_binding(&keys, &mouse_buttons, &gamepad_buttons) {
+            bindings.rebind(action, slot, binding);
+            input::save_bindings(&mut pkv, &bindings);
+            target.0 = None;
+        }
+        return;
+    }
+
+    for (interaction, mut border_color, mut rebind_button) in rebind_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                rebind_button.2 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !rebind_button.2 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    rebind_button.2 = true;
+                }
+            }
+            Interaction::Pressed => {
+                sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+                target.0 = Some((rebind_button.0, rebind_button.1));
+            }
+        }
+    }
+
+    for (interaction, mut border_color, mut reset_button) in reset_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                reset_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !reset_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    reset_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+                *bindings = input::types::InputBindings::default();
+                input::save_bindings(&mut pkv, &bindings);
+            }
+        }
+    }
+
+    let mut leave = action_state.just_pressed(input::types::Action::Back);
+
+    for (interaction, mut border_color, mut return_button) in return_to_menu_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                return_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !return_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    return_button.0 = true;
+                }
+            }
+            Interaction::Pressed => leave = true,
+        }
+    }
+
+    if leave && fade_to_black_query.iter().count() == 0 {
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+        commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+    }
+}
+
+pub fn update_binding_text(
+    target: Res<RebindTarget>,
+    bindings: Res<input::types::InputBindings>,
+    mut binding_text_query: Query<(&mut Text, &types::BindingText)>,
+) {
+    for (mut text, binding_text) in binding_text_query.iter_mut() {
+        let value = if target.0 == Some((binding_text.0, binding_text.1)) {
+            "Press, Back cancels".to_string()
+        } else {
+            bindings
+                .get(binding_text.0)
+                .get(binding_text.1)
+                .map_or("-".to_string(), |binding| binding.label())
+        };
+
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
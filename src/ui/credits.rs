@@ -1,10 +1,6 @@
-use bevy::{
-    audio::{PlaybackMode, Volume},
-    color::palettes::basic,
-    prelude::*,
-};
+use bevy::{color::palettes::basic, prelude::*};
 
-use crate::state;
+use crate::{audio, input, state};
 
 use super::types;
 
@@ -141,9 +137,12 @@ pub fn update_credits(
         &mut BorderColor,
         &mut types::ReturnToMenuButton,
     )>,
+    action_state: Res<input::types::ActionState>,
     fade_to_black_query: Query<&types::FadeToBlack>,
-    asset_server: Res<AssetServer>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
 ) {
+    let mut leave = action_state.just_pressed(input::types::Action::Back);
+
     for (interaction, mut border_color, mut play_button) in return_to_menu_query.iter_mut() {
         match *interaction {
             Interaction::None => {
@@ -154,31 +153,17 @@ pub fn update_credits(
                 *border_color = BorderColor(Color::from(basic::LIME));
 
                 if !play_button.0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/hover.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
                     play_button.0 = true;
                 }
             }
-            Interaction::Pressed => {
-                if fade_to_black_query.iter().count() == 0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/select.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
-
-                    commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
-                }
-            }
+            Interaction::Pressed => leave = true,
         }
     }
+
+    if leave && fade_to_black_query.iter().count() == 0 {
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+        commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+    }
 }
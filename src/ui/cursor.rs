@@ -1,6 +1,5 @@
 use super::types;
-use crate::{rendering, state};
-use avian2d::prelude::*;
+use crate::{rendering, replay, settings, state};
 use bevy::{prelude::*, window::PrimaryWindow};
 
 pub struct CursorPlugin;
@@ -10,12 +9,25 @@ impl Plugin for CursorPlugin {
         app.add_systems(OnEnter(state::GameState::Menu), spawn_cursor)
             .add_systems(
                 Update,
-                update_cursor.run_if(not(in_state(state::GameState::Splash))),
+                (
+                    update_cursor.run_if(not(in_state(state::GameState::Splash)).and_then(not(
+                        replay::is_playback.and_then(in_state(state::PauseState::Running)),
+                    ))),
+                    resize_cursor.run_if(resource_changed::<settings::Settings>),
+                ),
             );
     }
 }
 
-pub fn spawn_cursor(mut commands: Commands, asset_server: Res<AssetServer>) {
+//Cursor sprite size in pixels before the size setting, and how far its tip is from the left edge
+pub const CURSOR_SIZE: f32 = 30.0;
+pub const CURSOR_TIP: f32 = 6.0;
+
+pub fn spawn_cursor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<settings::Settings>,
+) {
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -34,8 +46,8 @@ pub fn spawn_cursor(mut commands: Commands, asset_server: Res<AssetServer>) {
                         ..Default::default()
                     },
                     style: Style {
-                        width: Val::Px(30.0),
-                        height: Val::Px(30.0),
+                        width: Val::Px(CURSOR_SIZE * settings.cursor_size),
+                        height: Val::Px(CURSOR_SIZE * settings.cursor_size),
                         top: Val::Px(50.0),
                         left: Val::Px(50.0),
                         ..Default::default()
@@ -45,52 +57,56 @@ pub fn spawn_cursor(mut commands: Commands, asset_server: Res<AssetServer>) {
                 types::Cursor,
             ));
         });
-
-    commands.spawn((
-        TransformBundle {
-            local: Transform::from_xyz(0.0, 0.0, 0.0),
-            ..Default::default()
-        },
-        RigidBody::Kinematic,
-        types::GhostCursor,
-    ));
 }
 
 pub fn update_cursor(
     window_query: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<rendering::types::OuterCamera>>,
-    mut ghost_cursor_query: Query<&mut Transform, With<types::GhostCursor>>,
+    mut pending_input: ResMut<replay::types::PendingInput>,
     mut cursor_query: Query<&mut Style, With<types::Cursor>>,
     touches: Res<Touches>,
+    settings: Res<settings::Settings>,
 ) {
     let window = window_query.single();
     let (camera, camera_transform) = camera_query.single();
 
-    let pos = match window.cursor_position() {
-        Some(pos) => pos,
-        None => {
-            let mut pos = Vec2::ZERO;
-            for finger in touches.iter() {
-                pos = finger.position();
-            }
-            if pos != Vec2::ZERO {
-                return;
-            } else {
-                pos
-            }
+    let mut shown = None;
+
+    //Every finger moves its own ghost cursor
+    for finger in touches.iter() {
+        if let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, finger.position()) {
+            pending_input
+                .pointer(replay::types::PointerId::Touch(finger.id()))
+                .cursor = world_pos;
+            shown = Some(finger.position());
         }
-    };
+    }
+
+    if let Some(pos) = window.cursor_position() {
+        if let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, pos) {
+            pending_input
+                .pointer(replay::types::PointerId::Mouse)
+                .cursor = world_pos;
+            shown = Some(pos);
+        }
+    }
 
-    let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, pos) else {
+    let Some(pos) = shown else {
         return;
     };
 
-    for mut transform in ghost_cursor_query.iter_mut() {
-        transform.translation = world_pos.extend(1.0);
+    for mut style in cursor_query.iter_mut() {
+        style.top = Val::Px(pos.y);
+        style.left = Val::Px((pos.x - CURSOR_TIP * settings.cursor_size).abs());
     }
+}
 
+pub fn resize_cursor(
+    settings: Res<settings::Settings>,
+    mut cursor_query: Query<&mut Style, With<types::Cursor>>,
+) {
     for mut style in cursor_query.iter_mut() {
-        style.top = Val::Px(pos.y);
-        style.left = Val::Px((pos.x - 6.0).abs());
+        style.width = Val::Px(CURSOR_SIZE * settings.cursor_size);
+        style.height = Val::Px(CURSOR_SIZE * settings.cursor_size);
     }
 }
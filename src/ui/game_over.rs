@@ -1,8 +1,8 @@
 use super::types;
-use crate::{score, state};
+use crate::{audio, input, leaderboard, level, mode, replay, restart, score, state};
 use bevy::{
-    audio::{PlaybackMode, Volume},
     color::palettes::basic,
+    input::keyboard::{Key, KeyboardInput},
     prelude::*,
 };
 use bevy_pkv::PkvStore;
@@ -13,49 +13,189 @@ impl Plugin for GameOverPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<types::GameOverEvent>()
             .add_systems(
-                FixedUpdate,
-                game_over_event.run_if(in_state(state::PauseState::Running)),
+                Update,
+                game_over_event.run_if(
+                    in_state(state::PauseState::Running)
+                        .or_else(in_state(state::PauseState::Paused)),
+                ),
+            )
+            .add_systems(
+                OnEnter(state::PauseState::GameOver),
+                (
+                    spawn_game_over_screen,
+                    |mut ducking: ResMut<audio::types::MusicDucking>| {
+                        ducking.duck(audio::types::DuckReason::GameOver, 0.4)
+                    },
+                ),
+            )
+            .add_systems(OnExit(state::PauseState::GameOver), leave_name_entry)
+            //Kept until the next run starts, so leaving the game can tell whether the run was recorded
+            .add_systems(
+                OnExit(state::PauseState::Loading),
+                |mut commands: Commands| commands.remove_resource::<types::LastRun>(),
+            )
+            .add_systems(
+                OnExit(state::GameState::InGame),
+                save_quit_zen_run.run_if(
+                    not(resource_exists::<types::LastRun>).and_then(not(replay::is_playback)),
+                ),
             )
-            .add_systems(OnEnter(state::PauseState::GameOver), spawn_game_over_screen)
             .add_systems(
                 OnExit(state::PauseState::GameOver),
-                |mut audio_query: Query<&mut PlaybackSettings, With<types::GlobalMusic>>| {
-                    for mut playback_settings in audio_query.iter_mut() {
-                        playback_settings.volume = Volume::new(0.2);
-                    }
+                |mut ducking: ResMut<audio::types::MusicDucking>| {
+                    ducking.release(audio::types::DuckReason::GameOver)
                 },
             )
             .add_systems(
                 Update,
-                update_game_over_screen.run_if(in_state(state::PauseState::GameOver)),
+                (
+                    update_game_over_screen.before(restart::restart_run),
+                    update_name_entry,
+                )
+                    .chain()
+                    .run_if(in_state(state::PauseState::GameOver)),
             );
     }
 }
 
 pub fn game_over_event(
+    mut commands: Commands,
     mut game_over_event_reader: EventReader<types::GameOverEvent>,
     mut pkv: ResMut<PkvStore>,
+    mut leaderboards: ResMut<leaderboard::Leaderboards>,
     mut next_state: ResMut<NextState<state::PauseState>>,
+    replay_mode: Res<replay::types::ReplayMode>,
 ) {
-    for event in game_over_event_reader.read() {
-        if pkv.get::<u64>("highscore").unwrap_or(0) < event.final_score {
-            pkv.set("highscore", &event.final_score)
-                .unwrap_or_else(|_| error!("Failed to store high score!"));
+    //Only the first cause counts, the clock may run out on the same tick the floor overflows
+    let Some(event) = game_over_event_reader.read().next().cloned() else {
+        return;
+    };
+    game_over_event_reader.clear();
+
+    if matches!(*next_state, NextState::Pending(state::PauseState::GameOver)) {
+        return;
+    }
+
+    let game_mode = event.summary.mode;
+    let final_score = event.summary.stats.score;
+    let new_highscore = !replay_mode.is_playback() && final_score > leaderboards.best(game_mode);
+
+    //Runs that make the board go in under the last name used, the player can change it.
+    //Replays already made the board when they were played.
+    let rank = if !replay_mode.is_playback() && leaderboards.qualifies(game_mode, final_score) {
+        let name = leaderboards.last_name.clone();
+        let rank = leaderboards.insert(
+            game_mode,
+            leaderboard::LeaderboardEntry::new(&name, &event.summary),
+        );
+        leaderboard::save_leaderboards(&mut pkv, &leaderboards);
+
+        if let Some(rank) = rank {
+            commands.insert_resource(types::NameEntry {
+                mode: game_mode,
+                rank,
+                name,
+            });
         }
+        rank
+    } else {
+        None
+    };
+
+    commands.insert_resource(types::LastRun {
+        reason: event.reason,
+        summary: event.summary,
+        new_highscore,
+        rank,
+    });
+
+    next_state.set(state::PauseState::GameOver);
+}
 
-        next_state.set(state::PauseState::GameOver);
+//Zen runs have no game over of their own, quitting to the desktop from the pause menu
+//still puts them on the board
+pub fn save_quit_zen_run(
+    mut pkv: ResMut<PkvStore>,
+    mut leaderboards: ResMut<leaderboard::Leaderboards>,
+    summarizer: score::RunSummarizer,
+) {
+    let summary = summarizer.summary();
+    if summary.mode != mode::GameMode::Zen
+        || !leaderboards.qualifies(summary.mode, summary.stats.score)
+    {
+        return;
     }
+
+    let name = leaderboards.last_name.clone();
+    leaderboards.insert(
+        summary.mode,
+        leaderboard::LeaderboardEntry::new(&name, &summary),
+    );
+    leaderboard::save_leaderboards(&mut pkv, &leaderboards);
+}
+
+fn spawn_button<T: Component>(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    button: T,
+    label: &str,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                border_color: BorderColor(Color::from(basic::GREEN)),
+                border_radius: BorderRadius::all(Val::Px(3.0)),
+                style: Style {
+                    width: Val::Percent(16.0),
+                    height: Val::Percent(6.5),
+                    border: UiRect::all(Val::Px(5.0)),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    margin: UiRect::all(Val::Percent(1.0)),
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(Color::from(basic::GREEN)),
+                ..Default::default()
+            },
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: asset_server.load("ui/fonts/font.otf"),
+                    font_size: 20.0,
+                    color: Color::from(basic::LIME),
+                },
+            ));
+        });
 }
 
 pub fn spawn_game_over_screen(
     mut commands: Commands,
-    mut score: ResMut<score::PlayerScore>,
     asset_server: Res<AssetServer>,
-    mut audio_query: Query<&mut PlaybackSettings, With<types::GlobalMusic>>,
+    last_run: Option<Res<types::LastRun>>,
+    summarizer: score::RunSummarizer,
+    leaderboards: Res<leaderboard::Leaderboards>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
 ) {
-    for mut playback_settings in audio_query.iter_mut() {
-        playback_settings.volume = Volume::new(0.08);
-    }
+    //Runs stopped without an event, like replays hitting their end, still get a summary
+    let (title, summary, new_highscore, rank) = match last_run {
+        Some(last_run) => (
+            last_run.reason.title(),
+            last_run.summary.clone(),
+            last_run.new_highscore,
+            last_run.rank,
+        ),
+        None => ("Game over!", summarizer.summary(), false, None),
+    };
+    let stats = &summary.stats;
+
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: asset_server.load("ui/fonts/font.otf"),
+        font_size,
+        color,
+    };
 
     commands
         .spawn((
@@ -75,65 +215,110 @@ pub fn spawn_game_over_screen(
             StateScoped(state::PauseState::GameOver),
         ))
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_sections([
+            let mut sections = vec![
+                TextSection::new(format!("{}\n", title), text_style(45.0, Color::WHITE)),
                 TextSection::new(
-                    "Game over!\n",
-                    TextStyle {
-                        font: asset_server.load("ui/fonts/font.otf"),
-                        font_size: 45.0,
-                        color: Color::WHITE,
+                    if new_highscore {
+                        "New high score!\n"
+                    } else {
+                        ""
                     },
+                    text_style(30.0, Color::from(basic::YELLOW)),
                 ),
                 TextSection::new(
-                    format!("Final score: {}", score.score),
-                    TextStyle {
-                        font: asset_server.load("ui/fonts/font.otf"),
-                        font_size: 30.0,
-                        color: Color::WHITE,
-                    },
+                    format!("Final score: {}\n", stats.score),
+                    text_style(30.0, Color::WHITE),
                 ),
-            ]));
+                TextSection::new(
+                    format!(
+                        "{}  Seed {}  {}:{:02}\n",
+                        summary.mode.name(),
+                        summary.seed,
+                        summary.duration as u64 / 60,
+                        summary.duration as u64 % 60
+                    ),
+                    text_style(20.0, Color::WHITE),
+                ),
+                TextSection::new(
+                    format!(
+                        "Sorted {}  Wrong {}  Accuracy {:.0}%\n",
+                        stats.sorted,
+                        stats.missorted,
+                        stats.accuracy() * 100.0
+                    ),
+                    text_style(20.0, Color::WHITE),
+                ),
+            ];
+
+            for (category, binned) in level::types::TrashCategory::ALL
+                .iter()
+                .zip(stats.binned.iter())
+            {
+                sections.push(TextSection::new(
+                    format!("{} {}  ", category.name(), binned),
+                    text_style(20.0, category.color()),
+                ));
+            }
+
+            sections.push(TextSection::new(
+                format!(
+                    "\nBase {}  Combo +{}  Streak +{}  Tricks +{}  Wrong bins -{}\nBest combo {}  Best streak {}  Tricks {}\n",
+                    stats.breakdown.base,
+                    stats.breakdown.combo,
+                    stats.breakdown.streak,
+                    stats.breakdown.tricks,
+                    stats.breakdown.penalties,
+                    stats.best_combo,
+                    stats.best_streak,
+                    stats.tricks
+                ),
+                text_style(20.0, Color::WHITE),
+            ));
+
+            if let Some(best_shot) = &stats.best_shot {
+                sections.push(TextSection::new(
+                    format!(
+                        "Best shot +{}: {}",
+                        best_shot.points,
+                        best_shot
+                            .tricks
+                            .iter()
+                            .map(|trick| trick.label())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    text_style(20.0, Color::WHITE),
+                ));
+            }
+
+            parent.spawn(TextBundle::from_sections(sections).with_text_justify(JustifyText::Center));
+
+            if let Some(rank) = rank {
+                parent.spawn((
+                    TextBundle::from_section(
+                        name_entry_line(rank, &leaderboards.last_name, true),
+                        text_style(20.0, Color::from(basic::YELLOW)),
+                    ),
+                    types::NameEntryText,
+                ));
+            }
 
             parent
-                .spawn((
-                    ButtonBundle {
-                        border_color: BorderColor(Color::from(basic::GREEN)),
-                        border_radius: BorderRadius::all(Val::Px(3.0)),
-                        style: Style {
-                            width: Val::Percent(16.0),
-                            height: Val::Percent(6.5),
-                            border: UiRect::all(Val::Px(5.0)),
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::Center,
-                            margin: UiRect::all(Val::Percent(1.0)),
-                            ..Default::default()
-                        },
-                        background_color: BackgroundColor(Color::from(basic::GREEN)),
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        justify_content: JustifyContent::Center,
                         ..Default::default()
                     },
-                    types::ReturnToMenuButton(false),
-                ))
+                    ..Default::default()
+                })
                 .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "Continue",
-                        TextStyle {
-                            font: asset_server.load("ui/fonts/font.otf"),
-                            font_size: 20.0,
-                            color: Color::from(basic::LIME),
-                        },
-                    ));
+                    spawn_button(parent, &asset_server, types::RetryButton(false), "Retry");
+                    spawn_button(parent, &asset_server, types::ReturnToMenuButton(false), "Menu");
                 });
         });
 
-    score.score = 0;
-    commands.spawn(AudioBundle {
-        source: asset_server.load("sounds/game/gameover.ogg"),
-        settings: PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            volume: Volume::new(0.5),
-            ..Default::default()
-        },
-    });
+    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::GameOver));
 }
 
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
@@ -145,12 +330,21 @@ pub fn update_game_over_screen(
             &mut BorderColor,
             &mut types::ReturnToMenuButton,
         ),
-        Without<types::QuitButton>,
+        Without<types::RetryButton>,
     >,
-
+    mut retry_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::RetryButton),
+        Without<types::ReturnToMenuButton>,
+    >,
+    action_state: Res<input::types::ActionState>,
     fade_to_black_query: Query<&types::FadeToBlack>,
-    asset_server: Res<AssetServer>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+    mut restart_event_writer: EventWriter<restart::RestartEvent>,
+    name_entry: Option<Res<types::NameEntry>>,
 ) {
+    //While a name is being typed, Back only finishes typing it
+    let mut leave = name_entry.is_none() && action_state.just_pressed(input::types::Action::Back);
+
     for (interaction, mut border_color, mut play_button) in return_to_menu_query.iter_mut() {
         match *interaction {
             Interaction::None => {
@@ -161,31 +355,138 @@ pub fn update_game_over_screen(
                 *border_color = BorderColor(Color::from(basic::LIME));
 
                 if !play_button.0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/hover.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
                     play_button.0 = true;
                 }
             }
+            Interaction::Pressed => leave = true,
+        }
+    }
+
+    for (interaction, mut border_color, mut retry_button) in retry_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                retry_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !retry_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    retry_button.0 = true;
+                }
+            }
             Interaction::Pressed => {
                 if fade_to_black_query.iter().count() == 0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/select.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
-
-                    commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                    restart_event_writer.send(restart::RestartEvent);
                 }
             }
         }
     }
+
+    if leave && fade_to_black_query.iter().count() == 0 {
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+        commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+    }
+}
+
+fn name_entry_line(rank: usize, name: &str, editing: bool) -> String {
+    if editing {
+        format!(
+            "#{} on the leaderboard! Name: {}_ (Enter to save)",
+            rank + 1,
+            name
+        )
+    } else {
+        format!("#{} on the leaderboard: {}", rank + 1, name)
+    }
+}
+
+//Types the name for a run that made the leaderboard, it is saved as it changes
+#[allow(clippy::too_many_arguments)]
+pub fn update_name_entry(
+    mut commands: Commands,
+    name_entry: Option<ResMut<types::NameEntry>>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    action_state: Res<input::types::ActionState>,
+    mut leaderboards: ResMut<leaderboard::Leaderboards>,
+    mut pkv: ResMut<PkvStore>,
+    mut name_entry_text_query: Query<&mut Text, With<types::NameEntryText>>,
+) {
+    let Some(mut name_entry) = name_entry else {
+        keyboard_events.clear();
+        return;
+    };
+
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(characters) => {
+                for character in characters.chars() {
+                    if (character.is_alphanumeric() || character == ' ')
+                        && name_entry.name.chars().count() < leaderboard::NAME_LENGTH
+                    {
+                        name_entry.name.push(character);
+                    }
+                }
+            }
+            Key::Space if name_entry.name.chars().count() < leaderboard::NAME_LENGTH => {
+                name_entry.name.push(' ');
+            }
+            Key::Backspace => {
+                name_entry.name.pop();
+            }
+            _ => (),
+        }
+    }
+
+    let done = action_state.just_pressed(input::types::Action::Confirm)
+        || action_state.just_pressed(input::types::Action::Back);
+
+    if done {
+        finish_name_entry(&mut commands, &name_entry, &mut leaderboards, &mut pkv);
+    }
+
+    if name_entry.is_changed() || done {
+        for mut text in name_entry_text_query.iter_mut() {
+            text.sections[0].value = name_entry_line(name_entry.rank, &name_entry.name, !done);
+        }
+    }
+}
+
+fn finish_name_entry(
+    commands: &mut Commands,
+    name_entry: &types::NameEntry,
+    leaderboards: &mut leaderboard::Leaderboards,
+    pkv: &mut PkvStore,
+) {
+    let name = name_entry.name.trim();
+
+    if !name.is_empty() {
+        leaderboards.rename(name_entry.mode, name_entry.rank, name);
+        leaderboards.last_name = name.to_string();
+        leaderboard::save_leaderboards(pkv, leaderboards);
+    }
+
+    commands.remove_resource::<types::NameEntry>();
+}
+
+//Leaving the screen keeps whatever name was typed so far
+pub fn leave_name_entry(
+    mut commands: Commands,
+    name_entry: Option<Res<types::NameEntry>>,
+    mut leaderboards: ResMut<leaderboard::Leaderboards>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    if let Some(name_entry) = name_entry {
+        finish_name_entry(&mut commands, &name_entry, &mut leaderboards, &mut pkv);
+    }
 }
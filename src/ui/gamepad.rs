@@ -0,0 +1,207 @@
+use super::types;
+use crate::{input, rendering, replay, settings, state};
+use bevy::{prelude::*, ui::UiSystem, utils::HashMap, window::PrimaryWindow};
+
+//Virtual cursor speed in pixels per second, ramping up while the stick is held
+const CURSOR_BASE_SPEED: f32 = 250.0;
+const CURSOR_MAX_SPEED: f32 = 900.0;
+const CURSOR_ACCELERATION: f32 = 1200.0;
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadCursors>()
+            .init_resource::<GamepadFocus>()
+            .add_systems(
+                Update,
+                (sync_gamepad_cursors, update_gamepad_cursors)
+                    .chain()
+                    .run_if(not(in_state(state::GameState::Splash)).and_then(not(
+                        replay::is_playback.and_then(in_state(state::PauseState::Running)),
+                    ))),
+            )
+            //Menu actions share keys with the run's, Confirm would press buttons while grabbing
+            .add_systems(
+                PreUpdate,
+                navigate_focus
+                    .after(UiSystem::Focus)
+                    .after(input::update_action_state)
+                    .run_if(
+                        not(in_state(state::GameState::Splash))
+                            .and_then(not(in_state(state::PauseState::Running))),
+                    ),
+            );
+    }
+}
+
+pub struct GamepadCursor {
+    //Position in window pixels, like the window cursor
+    position: Vec2,
+    speed: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct GamepadCursors(HashMap<Gamepad, GamepadCursor>);
+
+//Button highlighted by the focus actions, only while a gamepad or the keyboard drives the menus
+#[derive(Resource, Default)]
+pub struct GamepadFocus {
+    focused: Option<Entity>,
+}
+
+//Controllers plugged in mid-run get a cursor, unplugged ones let go of whatever they held
+pub fn sync_gamepad_cursors(
+    gamepads: Res<Gamepads>,
+    mut cursors: ResMut<GamepadCursors>,
+    mut pending_input: ResMut<replay::types::PendingInput>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    for gamepad in gamepads.iter() {
+        cursors.0.entry(gamepad).or_insert_with(|| GamepadCursor {
+            position: Vec2::new(window.width(), window.height()) / 2.0,
+            speed: CURSOR_BASE_SPEED,
+        });
+    }
+
+    cursors.0.retain(|gamepad, _| {
+        let connected = gamepads.contains(*gamepad);
+        if !connected {
+            pending_input
+                .pointers
+                .remove(&replay::types::PointerId::Gamepad(gamepad.id));
+        }
+        connected
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_gamepad_cursors(
+    time: Res<Time>,
+    mut cursors: ResMut<GamepadCursors>,
+    axes: Res<Axis<GamepadAxis>>,
+    action_state: Res<input::types::ActionState>,
+    mut pending_input: ResMut<replay::types::PendingInput>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<rendering::types::OuterCamera>>,
+    mut cursor_query: Query<&mut Style, With<types::Cursor>>,
+    settings: Res<settings::Settings>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) =
+        (window_query.get_single(), camera_query.get_single())
+    else {
+        return;
+    };
+
+    for (gamepad, cursor) in cursors.0.iter_mut() {
+        let stick = Vec2::new(
+            axes.get(GamepadAxis::new(*gamepad, GamepadAxisType::LeftStickX))
+                .unwrap_or(0.0),
+            //Stick up is positive, window y grows downwards
+            -axes
+                .get(GamepadAxis::new(*gamepad, GamepadAxisType::LeftStickY))
+                .unwrap_or(0.0),
+        );
+
+        if stick == Vec2::ZERO {
+            cursor.speed = CURSOR_BASE_SPEED;
+        } else {
+            cursor.speed =
+                (cursor.speed + CURSOR_ACCELERATION * time.delta_seconds()).min(CURSOR_MAX_SPEED);
+            cursor.position = (cursor.position + stick * cursor.speed * time.delta_seconds())
+                .clamp(Vec2::ZERO, Vec2::new(window.width(), window.height()));
+
+            //The on-screen cursor follows whichever device moved last
+            for mut style in cursor_query.iter_mut() {
+                style.top = Val::Px(cursor.position.y);
+                style.left = Val::Px(
+                    (cursor.position.x - super::cursor::CURSOR_TIP * settings.cursor_size).abs(),
+                );
+            }
+        }
+
+        let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor.position) else {
+            continue;
+        };
+
+        let pointer = pending_input.pointer(replay::types::PointerId::Gamepad(gamepad.id));
+        pointer.cursor = world_pos;
+        if let Some(actions) = action_state.device(input::types::InputDevice::Gamepad(*gamepad)) {
+            replay::pointer_actions(pointer, actions);
+        }
+    }
+}
+
+//Moves a highlight between buttons and presses it with Confirm, on top of mouse hovering
+#[allow(clippy::type_complexity)]
+pub fn navigate_focus(
+    mut focus: ResMut<GamepadFocus>,
+    action_state: Res<input::types::ActionState>,
+    mut cursor_moved_event_reader: EventReader<CursorMoved>,
+    mut button_query: Query<
+        (
+            Entity,
+            &mut Interaction,
+            &GlobalTransform,
+            &InheritedVisibility,
+        ),
+        With<Button>,
+    >,
+) {
+    //The mouse takes over again as soon as it moves
+    if cursor_moved_event_reader.read().count() != 0 {
+        focus.focused = None;
+    }
+
+    let step: isize = if action_state.just_pressed(input::types::Action::FocusNext) {
+        1
+    } else if action_state.just_pressed(input::types::Action::FocusPrevious) {
+        -1
+    } else {
+        0
+    };
+    let confirm = action_state.just_pressed(input::types::Action::Confirm);
+
+    //Visible buttons in reading order, top to bottom then left to right
+    let mut order: Vec<(Entity, Vec3)> = button_query
+        .iter()
+        .filter(|(.., visibility)| visibility.get())
+        .map(|(entity, _, transform, _)| (entity, transform.translation()))
+        .collect();
+    order.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+    if order.is_empty() {
+        focus.focused = None;
+        return;
+    }
+
+    let current = focus
+        .focused
+        .and_then(|focused| order.iter().position(|(entity, _)| *entity == focused));
+
+    focus.focused = match (current, step) {
+        (Some(index), step) => {
+            Some(order[(index as isize + step).rem_euclid(order.len() as isize) as usize].0)
+        }
+        //First focus or Confirm press picks the top button
+        (None, _) if step != 0 || confirm => Some(order[0].0),
+        (None, _) => None,
+    };
+
+    let Some(focused) = focus.focused else {
+        return;
+    };
+
+    if let Ok((_, mut interaction, ..)) = button_query.get_mut(focused) {
+        let target = if confirm && current.is_some() {
+            Interaction::Pressed
+        } else {
+            Interaction::Hovered
+        };
+        interaction.set_if_neq(target);
+    }
+}
@@ -0,0 +1,326 @@
+use bevy::{color::palettes::basic, prelude::*};
+
+use crate::{audio, input, leaderboard, level, mode, state};
+
+use super::types;
+
+pub struct LeaderboardScreenPlugin;
+
+impl Plugin for LeaderboardScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(state::GameState::Leaderboard), spawn_leaderboard)
+            .add_systems(
+                Update,
+                (update_leaderboard, update_leaderboard_table)
+                    .chain()
+                    .run_if(in_state(state::GameState::Leaderboard)),
+            );
+    }
+}
+
+//Mode whose board is on screen, starts at the one picked in the menu
+#[derive(Resource)]
+pub struct ShownBoard(pub mode::GameMode);
+
+//Column titles and how much of a row each one takes
+const COLUMNS: [(&str, f32); 7] = [
+    ("#", 5.0),
+    ("Name", 22.0),
+    ("Score", 12.0),
+    ("Date", 16.0),
+    ("Time", 10.0),
+    ("Seed", 20.0),
+    ("Arena", 15.0),
+];
+
+pub fn spawn_leaderboard(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_mode: Res<mode::GameMode>,
+) {
+    commands.insert_resource(ShownBoard(*game_mode));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                z_index: ZIndex::Global(2),
+                background_color: BackgroundColor(Color::BLACK),
+                ..Default::default()
+            },
+            StateScoped(state::GameState::Leaderboard),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Leaderboard",
+                TextStyle {
+                    font: asset_server.load("ui/fonts/title_font.TTF"),
+                    font_size: 35.0,
+                    color: Color::from(basic::GREEN),
+                },
+            ));
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        border_color: BorderColor(Color::from(basic::GREEN)),
+                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                        style: Style {
+                            width: Val::Percent(20.0),
+                            height: Val::Percent(6.0),
+                            border: UiRect::all(Val::Px(5.0)),
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            margin: UiRect::all(Val::Percent(1.0)),
+                            ..Default::default()
+                        },
+                        background_color: BackgroundColor(Color::from(basic::GREEN)),
+                        ..Default::default()
+                    },
+                    types::ModeButton(false),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: asset_server.load("ui/fonts/font.otf"),
+                                font_size: 20.0,
+                                color: Color::from(basic::LIME),
+                            },
+                        ),
+                        types::ModeText,
+                    ));
+                });
+
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(80.0),
+                        height: Val::Percent(60.0),
+                        flex_direction: FlexDirection::Column,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                types::LeaderboardTable,
+            ));
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        border_color: BorderColor(Color::from(basic::GREEN)),
+                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                        style: Style {
+                            width: Val::Percent(18.0),
+                            height: Val::Percent(6.5),
+                            border: UiRect::all(Val::Px(5.0)),
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            margin: UiRect::all(Val::Percent(1.0)),
+                            ..Default::default()
+                        },
+                        background_color: BackgroundColor(Color::from(basic::GREEN)),
+                        ..Default::default()
+                    },
+                    types::ReturnToMenuButton(false),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back to menu",
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 20.0,
+                            color: Color::from(basic::LIME),
+                        },
+                    ));
+                });
+        });
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn update_leaderboard(
+    mut commands: Commands,
+    mut mode_button_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::ModeButton),
+        Changed<Interaction>,
+    >,
+    mut return_to_menu_query: Query<
+        (
+            &Interaction,
+            &mut BorderColor,
+            &mut types::ReturnToMenuButton,
+        ),
+        Without<types::ModeButton>,
+    >,
+    mut shown_board: ResMut<ShownBoard>,
+    action_state: Res<input::types::ActionState>,
+    fade_to_black_query: Query<&types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut mode_button) in mode_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                mode_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !mode_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    mode_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+                shown_board.0 = shown_board.0.next();
+            }
+        }
+    }
+
+    let mut leave = action_state.just_pressed(input::types::Action::Back);
+
+    for (interaction, mut border_color, mut return_button) in return_to_menu_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                return_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !return_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    return_button.0 = true;
+                }
+            }
+            Interaction::Pressed => leave = true,
+        }
+    }
+
+    if leave && fade_to_black_query.iter().count() == 0 {
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+        commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+    }
+}
+
+fn spawn_row(parent: &mut ChildBuilder, cells: [String; 7], style: &TextStyle) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0 / (leaderboard::LEADERBOARD_SIZE + 1) as f32),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for (cell, (_, width)) in cells.into_iter().zip(COLUMNS) {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Percent(width),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(cell, style.clone()));
+                    });
+            }
+        });
+}
+
+//Rebuilds the rows whenever another mode's board is picked
+#[allow(clippy::too_many_arguments)]
+pub fn update_leaderboard_table(
+    mut commands: Commands,
+    shown_board: Res<ShownBoard>,
+    leaderboards: Res<leaderboard::Leaderboards>,
+    levels: Res<level::layout::LevelLayouts>,
+    layouts: Res<Assets<level::layout::LevelLayout>>,
+    table_query: Query<Entity, With<types::LeaderboardTable>>,
+    mut mode_text_query: Query<&mut Text, With<types::ModeText>>,
+    asset_server: Res<AssetServer>,
+) {
+    if !shown_board.is_changed() {
+        return;
+    }
+
+    for mut text in mode_text_query.iter_mut() {
+        text.sections[0].value = format!("Mode: {}", shown_board.0.name());
+    }
+
+    let header_style = TextStyle {
+        font: asset_server.load("ui/fonts/font.otf"),
+        font_size: 18.0,
+        color: Color::from(basic::GREEN),
+    };
+    let entry_style = TextStyle {
+        color: Color::WHITE,
+        ..header_style.clone()
+    };
+
+    let board = leaderboards.board(shown_board.0);
+
+    for table in table_query.iter() {
+        commands.entity(table).despawn_descendants();
+        commands.entity(table).with_children(|parent| {
+            spawn_row(
+                parent,
+                COLUMNS.map(|(title, _)| title.to_string()),
+                &header_style,
+            );
+
+            if board.is_empty() {
+                parent.spawn(TextBundle::from_section("No runs yet", entry_style.clone()));
+            }
+
+            for (rank, entry) in board.iter().enumerate() {
+                let arena = layouts
+                    .get(&levels.0[entry.modifiers.level % levels.0.len()])
+                    .map_or_else(
+                        || format!("{}", entry.modifiers.level + 1),
+                        |level| level.name.clone(),
+                    );
+                let date = if entry.date == 0 {
+                    "-".to_string()
+                } else {
+                    entry.date_label()
+                };
+
+                spawn_row(
+                    parent,
+                    [
+                        format!("{}", rank + 1),
+                        entry.name.clone(),
+                        format!("{}", entry.score),
+                        date,
+                        format!(
+                            "{}:{:02}",
+                            entry.duration as u64 / 60,
+                            entry.duration as u64 % 60
+                        ),
+                        if entry.modifiers.fixed_seed {
+                            format!("{} (set)", entry.seed)
+                        } else {
+                            format!("{}", entry.seed)
+                        },
+                        arena,
+                    ],
+                    &entry_style,
+                );
+            }
+        });
+    }
+}
@@ -31,6 +31,10 @@ impl LoadingData {
 #[derive(Resource, Default)]
 pub struct PipelinesReady(pub bool);
 
+//Path of an asset the last run could not load, shown on the menu it went back to
+#[derive(Resource)]
+pub struct LoadFailed(pub String);
+
 pub struct PipelinesReadyPlugin;
 
 impl Plugin for PipelinesReadyPlugin {
@@ -60,7 +64,12 @@ impl Plugin for LoadingScreenPlugin {
             .insert_resource(LoadingData::new(self.confirmation_frames_target))
             .insert_resource(LoadingState::Loading)
             .add_systems(Update, update_loading_data)
-            .add_systems(OnEnter(state::GameState::InGame), setup_loading_screen)
+            .add_systems(
+                OnEnter(state::GameState::InGame),
+                (setup_loading_screen, |mut commands: Commands| {
+                    commands.remove_resource::<LoadFailed>()
+                }),
+            )
             .add_systems(
                 Update,
                 display_loading_screen.run_if(in_state(state::GameState::InGame)),
@@ -80,20 +89,44 @@ fn update_pipelines_ready(mut main_world: ResMut<MainWorld>, pipelines: Res<Pipe
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_loading_data(
+    mut commands: Commands,
     mut loading_data: ResMut<LoadingData>,
     mut loading_state: ResMut<LoadingState>,
     pipelines_ready: Res<PipelinesReady>,
     mut next_state: ResMut<NextState<state::PauseState>>,
+    mut next_game_state: ResMut<NextState<state::GameState>>,
     asset_server: Res<AssetServer>,
+    camera_query: Query<Entity, With<LoadingCamera>>,
 ) {
+    //Assets that failed (a data file that does not validate) never finish, so the run can not start
+    let failed = loading_data
+        .assets
+        .iter()
+        .find(|asset| matches!(asset_server.load_state(asset.id()), LoadState::Failed(_)));
+    if let Some(failed) = failed {
+        let path = failed
+            .path()
+            .map_or("an asset".to_string(), |path| path.to_string());
+        error!("Failed to load {}, going back to the menu", path);
+
+        commands.insert_resource(LoadFailed(path));
+        for entity in camera_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        loading_data.assets.clear();
+        next_game_state.set(state::GameState::Menu);
+        return;
+    }
+
     if !loading_data.assets.is_empty() || !pipelines_ready.0 {
         next_state.set(state::PauseState::Loading);
         loading_data.confirmation_frames_count = 0;
 
         let mut pop_list: Vec<usize> = Vec::new();
         for (index, asset) in loading_data.assets.iter().enumerate() {
-            if let Some(LoadState::Loaded) = asset_server.get_load_state(asset) {
+            if asset_server.is_loaded_with_dependencies(asset) {
                 pop_list.push(index);
             }
         }
@@ -1,34 +1,41 @@
 use super::types;
-use crate::{state, ui};
+use crate::{audio, leaderboard, level, mode, rng, state, ui};
 use bevy::{
-    audio::{PlaybackMode, Volume},
     color::palettes::basic,
+    input::keyboard::{Key, KeyboardInput},
     prelude::*,
 };
-use bevy_pkv::PkvStore;
 
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(state::GameState::Menu), setup_menu)
-            .add_systems(Update, update_menu.run_if(in_state(state::GameState::Menu)));
+            .add_systems(
+                Update,
+                (
+                    update_menu,
+                    update_controls_button,
+                    update_leaderboard_button,
+                    update_stats_button,
+                    update_achievements_button,
+                    update_settings_button,
+                    update_mode_select,
+                    update_level_select,
+                    update_seed_select,
+                )
+                    .run_if(in_state(state::GameState::Menu)),
+            );
     }
 }
 
-pub fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<PkvStore>) {
-    commands.spawn((
-        AudioBundle {
-            source: asset_server.load("sounds/game/nothingon.ogg"),
-            settings: PlaybackSettings {
-                volume: Volume::new(0.2),
-                mode: PlaybackMode::Loop,
-                ..Default::default()
-            },
-        },
-        types::GlobalMusic,
-    ));
-
+pub fn setup_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    leaderboards: Res<leaderboard::Leaderboards>,
+    game_mode: Res<mode::GameMode>,
+    load_failed: Option<Res<ui::loading::LoadFailed>>,
+) {
     commands
         .spawn((
             NodeBundle {
@@ -106,15 +113,29 @@ pub fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>, pkv: R
                                     ));
                                 });
 
-                            parent.spawn(TextBundle::from_section(
-                                format!("Highscore: {}", pkv.get::<u64>("highscore").unwrap_or(0)),
-                                TextStyle {
-                                    font: asset_server.load("ui/fonts/title_font.TTF"),
-                                    font_size: 28.0,
-                                    color: Color::from(basic::GREEN),
-                                },
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    format!("Highscore: {}", leaderboards.best(*game_mode)),
+                                    TextStyle {
+                                        font: asset_server.load("ui/fonts/title_font.TTF"),
+                                        font_size: 28.0,
+                                        color: Color::from(basic::GREEN),
+                                    },
+                                ),
+                                types::HighscoreText,
                             ));
 
+                            if let Some(load_failed) = load_failed {
+                                parent.spawn(TextBundle::from_section(
+                                    format!("Could not load {}, see the log", load_failed.0),
+                                    TextStyle {
+                                        font: asset_server.load("ui/fonts/font.otf"),
+                                        font_size: 18.0,
+                                        color: Color::from(basic::RED),
+                                    },
+                                ));
+                            }
+
                             parent
                                 .spawn((
                                     ButtonBundle {
@@ -147,6 +168,271 @@ pub fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>, pkv: R
                                     ));
                                 });
 
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        border_color: BorderColor(Color::from(basic::GREEN)),
+                                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                                        style: Style {
+                                            width: Val::Percent(20.0),
+                                            height: Val::Percent(5.0),
+                                            border: UiRect::all(Val::Px(5.0)),
+                                            align_items: AlignItems::Center,
+                                            justify_content: JustifyContent::Center,
+                                            margin: UiRect::all(Val::Percent(1.0)),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::GREEN,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::ModeButton(false),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        TextBundle::from_section(
+                                            format!("Mode: {}", game_mode.name()),
+                                            TextStyle {
+                                                font: asset_server.load("ui/fonts/font.otf"),
+                                                font_size: 20.0,
+                                                color: Color::from(basic::LIME),
+                                            },
+                                        ),
+                                        types::ModeText,
+                                    ));
+                                });
+
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        border_color: BorderColor(Color::from(basic::GREEN)),
+                                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                                        style: Style {
+                                            width: Val::Percent(20.0),
+                                            height: Val::Percent(5.0),
+                                            border: UiRect::all(Val::Px(5.0)),
+                                            align_items: AlignItems::Center,
+                                            justify_content: JustifyContent::Center,
+                                            margin: UiRect::all(Val::Percent(1.0)),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::GREEN,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::LevelButton(false),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        TextBundle::from_section(
+                                            "Arena: ...",
+                                            TextStyle {
+                                                font: asset_server.load("ui/fonts/font.otf"),
+                                                font_size: 20.0,
+                                                color: Color::from(basic::LIME),
+                                            },
+                                        ),
+                                        types::LevelText,
+                                    ));
+                                });
+
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        border_color: BorderColor(Color::from(basic::GREEN)),
+                                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                                        style: Style {
+                                            width: Val::Percent(20.0),
+                                            height: Val::Percent(5.0),
+                                            border: UiRect::all(Val::Px(5.0)),
+                                            align_items: AlignItems::Center,
+                                            justify_content: JustifyContent::Center,
+                                            margin: UiRect::all(Val::Percent(1.0)),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::GREEN,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::SeedButton(false),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        TextBundle::from_section(
+                                            "Seed: Random",
+                                            TextStyle {
+                                                font: asset_server.load("ui/fonts/font.otf"),
+                                                font_size: 20.0,
+                                                color: Color::from(basic::LIME),
+                                            },
+                                        ),
+                                        types::SeedText,
+                                    ));
+                                });
+
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        border_color: BorderColor(Color::from(basic::GREEN)),
+                                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                                        style: Style {
+                                            width: Val::Percent(20.0),
+                                            height: Val::Percent(5.0),
+                                            border: UiRect::all(Val::Px(5.0)),
+                                            align_items: AlignItems::Center,
+                                            justify_content: JustifyContent::Center,
+                                            margin: UiRect::all(Val::Percent(1.0)),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::GREEN,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::LeaderboardButton(false),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        "Leaderboard",
+                                        TextStyle {
+                                            font: asset_server.load("ui/fonts/font.otf"),
+                                            font_size: 20.0,
+                                            color: Color::from(basic::LIME),
+                                        },
+                                    ));
+                                });
+
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        border_color: BorderColor(Color::from(basic::GREEN)),
+                                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                                        style: Style {
+                                            width: Val::Percent(10.0),
+                                            height: Val::Percent(5.0),
+                                            border: UiRect::all(Val::Px(5.0)),
+                                            align_items: AlignItems::Center,
+                                            justify_content: JustifyContent::Center,
+                                            margin: UiRect::all(Val::Percent(1.0)),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::GREEN,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::StatsButton(false),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        "Stats",
+                                        TextStyle {
+                                            font: asset_server.load("ui/fonts/font.otf"),
+                                            font_size: 20.0,
+                                            color: Color::from(basic::LIME),
+                                        },
+                                    ));
+                                });
+
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        border_color: BorderColor(Color::from(basic::GREEN)),
+                                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                                        style: Style {
+                                            width: Val::Percent(20.0),
+                                            height: Val::Percent(5.0),
+                                            border: UiRect::all(Val::Px(5.0)),
+                                            align_items: AlignItems::Center,
+                                            justify_content: JustifyContent::Center,
+                                            margin: UiRect::all(Val::Percent(1.0)),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::GREEN,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::AchievementsButton(false),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        "Achievements",
+                                        TextStyle {
+                                            font: asset_server.load("ui/fonts/font.otf"),
+                                            font_size: 20.0,
+                                            color: Color::from(basic::LIME),
+                                        },
+                                    ));
+                                });
+
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        border_color: BorderColor(Color::from(basic::GREEN)),
+                                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                                        style: Style {
+                                            width: Val::Percent(15.0),
+                                            height: Val::Percent(5.0),
+                                            border: UiRect::all(Val::Px(5.0)),
+                                            align_items: AlignItems::Center,
+                                            justify_content: JustifyContent::Center,
+                                            margin: UiRect::all(Val::Percent(1.0)),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::GREEN,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::SettingsButton(false),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        "Settings",
+                                        TextStyle {
+                                            font: asset_server.load("ui/fonts/font.otf"),
+                                            font_size: 20.0,
+                                            color: Color::from(basic::LIME),
+                                        },
+                                    ));
+                                });
+
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        border_color: BorderColor(Color::from(basic::GREEN)),
+                                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                                        style: Style {
+                                            width: Val::Percent(10.0),
+                                            height: Val::Percent(5.0),
+                                            border: UiRect::all(Val::Px(5.0)),
+                                            align_items: AlignItems::Center,
+                                            justify_content: JustifyContent::Center,
+                                            margin: UiRect::all(Val::Percent(1.0)),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::GREEN,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::ControlsButton(false),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        "Controls",
+                                        TextStyle {
+                                            font: asset_server.load("ui/fonts/font.otf"),
+                                            font_size: 20.0,
+                                            color: Color::from(basic::LIME),
+                                        },
+                                    ));
+                                });
+
                             parent
                                 .spawn((
                                     ButtonBundle {
@@ -232,7 +518,7 @@ pub fn update_menu(
     >,
 
     fade_to_black_query: Query<&ui::types::FadeToBlack>,
-    asset_server: Res<AssetServer>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
 ) {
     for (interaction, mut border_color, mut play_button) in play_button_query.iter_mut() {
         match *interaction {
@@ -244,27 +530,14 @@ pub fn update_menu(
                 *border_color = BorderColor(Color::from(basic::LIME));
 
                 if !play_button.0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/hover.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
                     play_button.0 = true;
                 }
             }
             Interaction::Pressed => {
                 if fade_to_black_query.iter().count() == 0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/select.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
 
                     commands.spawn(ui::types::FadeToBlack::new(0.5, state::GameState::InGame));
                 }
@@ -282,27 +555,14 @@ pub fn update_menu(
                 *border_color = BorderColor(Color::from(basic::LIME));
 
                 if !credits_button.0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/hover.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
                     credits_button.0 = true;
                 }
             }
             Interaction::Pressed => {
                 if fade_to_black_query.iter().count() == 0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/select.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
 
                     commands.spawn(ui::types::FadeToBlack::new(0.5, state::GameState::Credits));
                 }
@@ -320,28 +580,15 @@ pub fn update_menu(
                 *border_color = BorderColor(Color::from(basic::LIME));
 
                 if !quit_button.0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/hover.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
 
                     quit_button.0 = true;
                 }
             }
             Interaction::Pressed => {
                 if fade_to_black_query.iter().count() == 0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/select.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
 
                     commands.spawn(ui::types::FadeToBlack::new(0.5, state::GameState::Quitting));
                 }
@@ -349,3 +596,356 @@ pub fn update_menu(
         }
     }
 }
+
+pub fn update_controls_button(
+    mut commands: Commands,
+    mut controls_button_query: Query<(&Interaction, &mut BorderColor, &mut types::ControlsButton)>,
+    fade_to_black_query: Query<&ui::types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut controls_button) in controls_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                controls_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !controls_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    controls_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                if fade_to_black_query.iter().count() == 0 {
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                    commands.spawn(ui::types::FadeToBlack::new(0.5, state::GameState::Controls));
+                }
+            }
+        }
+    }
+}
+
+pub fn update_leaderboard_button(
+    mut commands: Commands,
+    mut leaderboard_button_query: Query<(
+        &Interaction,
+        &mut BorderColor,
+        &mut types::LeaderboardButton,
+    )>,
+    fade_to_black_query: Query<&ui::types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut leaderboard_button) in
+        leaderboard_button_query.iter_mut()
+    {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                leaderboard_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !leaderboard_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    leaderboard_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                if fade_to_black_query.iter().count() == 0 {
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                    commands.spawn(ui::types::FadeToBlack::new(
+                        0.5,
+                        state::GameState::Leaderboard,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+pub fn update_stats_button(
+    mut commands: Commands,
+    mut stats_button_query: Query<(&Interaction, &mut BorderColor, &mut types::StatsButton)>,
+    fade_to_black_query: Query<&ui::types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut stats_button) in stats_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                stats_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !stats_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    stats_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                if fade_to_black_query.iter().count() == 0 {
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                    commands.spawn(ui::types::FadeToBlack::new(0.5, state::GameState::Stats));
+                }
+            }
+        }
+    }
+}
+
+pub fn update_settings_button(
+    mut commands: Commands,
+    mut settings_button_query: Query<(&Interaction, &mut BorderColor, &mut types::SettingsButton)>,
+    fade_to_black_query: Query<&ui::types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut settings_button) in settings_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                settings_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !settings_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    settings_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                if fade_to_black_query.iter().count() == 0 {
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                    commands.spawn(ui::types::FadeToBlack::new(0.5, state::GameState::Settings));
+                }
+            }
+        }
+    }
+}
+
+pub fn update_achievements_button(
+    mut commands: Commands,
+    mut achievements_button_query: Query<(
+        &Interaction,
+        &mut BorderColor,
+        &mut types::AchievementsButton,
+    )>,
+    fade_to_black_query: Query<&ui::types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut achievements_button) in
+        achievements_button_query.iter_mut()
+    {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                achievements_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !achievements_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    achievements_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                if fade_to_black_query.iter().count() == 0 {
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                    commands.spawn(ui::types::FadeToBlack::new(
+                        0.5,
+                        state::GameState::Achievements,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+//Cycles through the game modes, the highscore shown is the selected mode's
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn update_mode_select(
+    mut mode_button_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::ModeButton),
+        Changed<Interaction>,
+    >,
+    mut mode_text_query: Query<&mut Text, (With<types::ModeText>, Without<types::HighscoreText>)>,
+    mut highscore_text_query: Query<
+        &mut Text,
+        (With<types::HighscoreText>, Without<types::ModeText>),
+    >,
+    mut game_mode: ResMut<mode::GameMode>,
+    leaderboards: Res<leaderboard::Leaderboards>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut mode_button) in mode_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                mode_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !mode_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    mode_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                *game_mode = game_mode.next();
+            }
+        }
+    }
+
+    if !game_mode.is_changed() {
+        return;
+    }
+
+    for mut text in mode_text_query.iter_mut() {
+        text.sections[0].value = format!("Mode: {}", game_mode.name());
+    }
+
+    for mut text in highscore_text_query.iter_mut() {
+        text.sections[0].value = format!("Highscore: {}", leaderboards.best(*game_mode));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_level_select(
+    mut level_button_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::LevelButton),
+        Changed<Interaction>,
+    >,
+    mut level_text_query: Query<&mut Text, With<types::LevelText>>,
+    mut current_level: ResMut<level::layout::CurrentLevel>,
+    levels: Res<level::layout::LevelLayouts>,
+    layouts: Res<Assets<level::layout::LevelLayout>>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut level_button) in level_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                level_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !level_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    level_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                current_level.0 = (current_level.0 + 1) % levels.0.len();
+            }
+        }
+    }
+
+    //Names show up once the layouts finish loading
+    let name = layouts
+        .get(levels.current(&current_level))
+        .map_or("...", |level| level.name.as_str());
+
+    for mut text in level_text_query.iter_mut() {
+        let value = format!("Arena: {}", name);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+//Click to type a seed, click again or press enter to confirm, leave empty for random
+#[allow(clippy::type_complexity)]
+pub fn update_seed_select(
+    mut seed_button_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::SeedButton),
+        Changed<Interaction>,
+    >,
+    mut seed_text_query: Query<&mut Text, With<types::SeedText>>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut seed_selection: ResMut<rng::SeedSelection>,
+    mut editing: Local<bool>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut seed_button) in seed_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                seed_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !seed_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    seed_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                *editing = !*editing;
+            }
+        }
+    }
+
+    for event in keyboard_events.read() {
+        if !*editing || !event.state.is_pressed() {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(character) => {
+                if let Some(digit) = character.chars().next().and_then(|c| c.to_digit(10)) {
+                    let seed = seed_selection.0.unwrap_or(0);
+                    if let Some(seed) = seed
+                        .checked_mul(10)
+                        .and_then(|seed| seed.checked_add(digit as u64))
+                    {
+                        seed_selection.0 = Some(seed);
+                    }
+                }
+            }
+            Key::Backspace => {
+                seed_selection.0 = seed_selection
+                    .0
+                    .map(|seed| seed / 10)
+                    .filter(|&seed| seed != 0);
+            }
+            Key::Enter => *editing = false,
+            _ => (),
+        }
+    }
+
+    let value = match (seed_selection.0, *editing) {
+        (Some(seed), true) => format!("Seed: {}_", seed),
+        (None, true) => "Seed: _".to_string(),
+        (Some(seed), false) => format!("Seed: {}", seed),
+        (None, false) => "Seed: Random".to_string(),
+    };
+
+    for mut text in seed_text_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
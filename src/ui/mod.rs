@@ -1,12 +1,18 @@
+pub mod achievements;
+pub mod controls;
 pub mod credits;
 pub mod cursor;
 pub mod fade_to_black;
 pub mod game_over;
+pub mod gamepad;
+pub mod leaderboard;
 pub mod loading;
 pub mod menu;
 pub mod pause;
 pub mod score;
+pub mod settings;
 pub mod splash;
+pub mod stats;
 pub mod types;
 
 use bevy::prelude::*;
@@ -36,11 +42,17 @@ impl Plugin for ReCycleUiPlugin {
             splash::SplashPlugin,
             menu::MenuPlugin,
             cursor::CursorPlugin,
+            gamepad::GamepadPlugin,
             score::ScoreUiPlugin,
             pause::PausePlugin,
             fade_to_black::FadeToBlackPlugin,
             game_over::GameOverPlugin,
             credits::CreditsPlugin,
+            controls::ControlsPlugin,
+            leaderboard::LeaderboardScreenPlugin,
+            stats::StatsScreenPlugin,
+            achievements::AchievementsUiPlugin,
+            settings::SettingsScreenPlugin,
         ));
     }
 }
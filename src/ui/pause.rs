@@ -1,11 +1,7 @@
 use super::types;
-use crate::state;
+use crate::{audio, input, restart, score, state};
 use avian2d::prelude::*;
-use bevy::{
-    audio::{PlaybackMode, Volume},
-    color::palettes::basic,
-    prelude::*,
-};
+use bevy::{color::palettes::basic, prelude::*};
 
 pub struct PausePlugin;
 
@@ -15,7 +11,12 @@ impl Plugin for PausePlugin {
             Update,
             (
                 open_pause_menu.run_if(in_state(state::PauseState::Running)),
-                (close_pause_menu, update_pause_menu).run_if(in_state(state::PauseState::Paused)),
+                (
+                    close_pause_menu,
+                    update_pause_menu.before(restart::restart_run),
+                    update_settings_button,
+                )
+                    .run_if(in_state(state::PauseState::Paused)),
             ),
         )
         .add_systems(
@@ -27,24 +28,35 @@ impl Plugin for PausePlugin {
         .add_systems(
             OnExit(state::PauseState::Paused),
             |mut time: ResMut<Time<Physics>>| time.unpause(),
+        )
+        //Settings are opened from the pause menu, so the run stays frozen there too
+        .add_systems(
+            OnEnter(state::PauseState::Settings),
+            |mut time: ResMut<Time<Physics>>| time.pause(),
+        )
+        .add_systems(
+            OnExit(state::PauseState::Settings),
+            |mut time: ResMut<Time<Physics>>| time.unpause(),
         );
     }
 }
 
 pub fn open_pause_menu(
-    key: Res<ButtonInput<KeyCode>>,
+    action_state: Res<input::types::ActionState>,
     mut next_state: ResMut<NextState<state::PauseState>>,
 ) {
-    if key.just_pressed(KeyCode::Escape) {
+    if action_state.just_pressed(input::types::Action::Pause) {
         next_state.set(state::PauseState::Paused);
     }
 }
 
 pub fn close_pause_menu(
-    key: Res<ButtonInput<KeyCode>>,
+    action_state: Res<input::types::ActionState>,
     mut next_state: ResMut<NextState<state::PauseState>>,
 ) {
-    if key.just_pressed(KeyCode::Escape) {
+    if action_state.just_pressed(input::types::Action::Pause)
+        || action_state.just_pressed(input::types::Action::Back)
+    {
         next_state.set(state::PauseState::Running);
     }
 }
@@ -88,7 +100,7 @@ pub fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>)
                                 border_color: BorderColor(Color::from(basic::GREEN)),
                                 border_radius: BorderRadius::all(Val::Px(3.0)),
                                 style: Style {
-                                    width: Val::Percent(25.0),
+                                    width: Val::Percent(20.0),
                                     height: Val::Percent(10.0),
                                     border: UiRect::all(Val::Px(5.0)),
                                     align_items: AlignItems::Center,
@@ -99,11 +111,11 @@ pub fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>)
                                 background_color: BackgroundColor(Color::from(basic::GREEN)),
                                 ..Default::default()
                             },
-                            types::ReturnToMenuButton(false),
+                            types::EndRunButton(false),
                         ))
                         .with_children(|parent| {
                             parent.spawn(TextBundle::from_section(
-                                "Return to menu",
+                                "End run",
                                 TextStyle {
                                     font: asset_server.load("ui/fonts/font.otf"),
                                     font_size: 20.0,
@@ -118,7 +130,66 @@ pub fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>)
                                 border_color: BorderColor(Color::from(basic::GREEN)),
                                 border_radius: BorderRadius::all(Val::Px(3.0)),
                                 style: Style {
-                                    width: Val::Percent(25.0),
+                                    width: Val::Percent(20.0),
+                                    height: Val::Percent(10.0),
+                                    border: UiRect::all(Val::Px(5.0)),
+                                    align_items: AlignItems::Center,
+                                    justify_content: JustifyContent::Center,
+                                    margin: UiRect::all(Val::Percent(1.0)),
+                                    ..Default::default()
+                                },
+                                background_color: BackgroundColor(Color::from(basic::GREEN)),
+                                ..Default::default()
+                            },
+                            types::RestartButton(false),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Restart",
+                                TextStyle {
+                                    font: asset_server.load("ui/fonts/font.otf"),
+                                    font_size: 20.0,
+                                    color: Color::from(basic::LIME),
+                                },
+                            ));
+                        });
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                border_color: BorderColor(Color::from(basic::GREEN)),
+                                border_radius: BorderRadius::all(Val::Px(3.0)),
+                                style: Style {
+                                    width: Val::Percent(20.0),
+                                    height: Val::Percent(10.0),
+                                    border: UiRect::all(Val::Px(5.0)),
+                                    align_items: AlignItems::Center,
+                                    justify_content: JustifyContent::Center,
+                                    margin: UiRect::all(Val::Percent(1.0)),
+                                    ..Default::default()
+                                },
+                                background_color: BackgroundColor(Color::from(basic::GREEN)),
+                                ..Default::default()
+                            },
+                            types::SettingsButton(false),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Settings",
+                                TextStyle {
+                                    font: asset_server.load("ui/fonts/font.otf"),
+                                    font_size: 20.0,
+                                    color: Color::from(basic::LIME),
+                                },
+                            ));
+                        });
+
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                border_color: BorderColor(Color::from(basic::GREEN)),
+                                border_radius: BorderRadius::all(Val::Px(3.0)),
+                                style: Style {
+                                    width: Val::Percent(20.0),
                                     height: Val::Percent(10.0),
                                     border: UiRect::all(Val::Px(5.0)),
                                     align_items: AlignItems::Center,
@@ -148,23 +219,26 @@ pub fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>)
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn update_pause_menu(
     mut commands: Commands,
-    mut return_to_menu_query: Query<
-        (
-            &Interaction,
-            &mut BorderColor,
-            &mut types::ReturnToMenuButton,
-        ),
-        Without<types::QuitButton>,
+    mut end_run_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::EndRunButton),
+        (Without<types::QuitButton>, Without<types::RestartButton>),
+    >,
+    mut restart_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::RestartButton),
+        (Without<types::QuitButton>, Without<types::EndRunButton>),
     >,
     mut quit_button_query: Query<
         (&Interaction, &mut BorderColor, &mut types::QuitButton),
-        Without<types::PlayButton>,
+        (Without<types::EndRunButton>, Without<types::RestartButton>),
     >,
 
     fade_to_black_query: Query<&types::FadeToBlack>,
-    asset_server: Res<AssetServer>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+    summarizer: score::RunSummarizer,
+    mut game_over_event_writer: EventWriter<types::GameOverEvent>,
+    mut restart_event_writer: EventWriter<restart::RestartEvent>,
 ) {
-    for (interaction, mut border_color, mut play_button) in return_to_menu_query.iter_mut() {
+    for (interaction, mut border_color, mut play_button) in end_run_query.iter_mut() {
         match *interaction {
             Interaction::None => {
                 *border_color = BorderColor(Color::from(basic::GREEN));
@@ -174,29 +248,42 @@ pub fn update_pause_menu(
                 *border_color = BorderColor(Color::from(basic::LIME));
 
                 if !play_button.0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/hover.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
                     play_button.0 = true;
                 }
             }
             Interaction::Pressed => {
                 if fade_to_black_query.iter().count() == 0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/select.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
-
-                    commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                    game_over_event_writer.send(summarizer.game_over(types::GameOverReason::Quit));
+                }
+            }
+        }
+    }
+
+    for (interaction, mut border_color, mut restart_button) in restart_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                restart_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !restart_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+
+                    restart_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                if fade_to_black_query.iter().count() == 0 {
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                    restart_event_writer.send(restart::RestartEvent);
                 }
             }
         }
@@ -212,28 +299,15 @@ pub fn update_pause_menu(
                 *border_color = BorderColor(Color::from(basic::LIME));
 
                 if !quit_button.0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/hover.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
 
                     quit_button.0 = true;
                 }
             }
             Interaction::Pressed => {
                 if fade_to_black_query.iter().count() == 0 {
-                    commands.spawn(AudioBundle {
-                        source: asset_server.load("ui/sounds/select.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new(0.5),
-                            ..Default::default()
-                        },
-                    });
+                    sfx_event_writer
+                        .send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
 
                     commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Quitting));
                 }
@@ -241,3 +315,32 @@ pub fn update_pause_menu(
         }
     }
 }
+
+pub fn update_settings_button(
+    mut settings_button_query: Query<(&Interaction, &mut BorderColor, &mut types::SettingsButton)>,
+    mut next_state: ResMut<NextState<state::PauseState>>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut settings_button) in settings_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                settings_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !settings_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+
+                    settings_button.0 = true;
+                }
+            }
+            Interaction::Pressed => {
+                sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+                next_state.set(state::PauseState::Settings);
+            }
+        }
+    }
+}
@@ -1,16 +1,54 @@
 use super::types;
-use crate::{level, score, state};
+use crate::{level, mode, score, state};
 use bevy::{color::palettes::basic, prelude::*};
 
 pub struct ScoreUiPlugin;
 
 impl Plugin for ScoreUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(state::GameState::InGame), spawn_score_ui)
+        //The HUD reads the difficulty curve, which is only there once loading is done
+        app.add_systems(OnExit(state::PauseState::Loading), spawn_score_ui)
             .add_systems(
                 Update,
-                update_score_ui.run_if(in_state(state::GameState::InGame)),
+                (
+                    update_score_ui,
+                    update_clutter_ui,
+                    update_combo_ui,
+                    update_trick_text,
+                )
+                    .run_if(
+                        in_state(state::GameState::InGame)
+                            .and_then(not(in_state(state::PauseState::Loading))),
+                    ),
             );
+
+        #[cfg(feature = "dev-tools")]
+        app.add_systems(
+            Update,
+            update_difficulty_debug.run_if(
+                in_state(state::GameState::InGame)
+                    .and_then(not(in_state(state::PauseState::Loading))),
+            ),
+        );
+    }
+}
+
+//Second HUD line, what the mode's run is measured against
+fn mode_line(
+    game_mode: mode::GameMode,
+    timer: Option<&level::types::TimeRemaining>,
+    starting_time: f32,
+) -> String {
+    let remaining = timer.map_or(game_mode.starting_time(starting_time), |timer| {
+        timer.remaining.max(0.0)
+    });
+    let elapsed = timer.map_or(0.0, |timer| timer.elapsed) as u32;
+
+    match game_mode {
+        mode::GameMode::Classic => format!("Time: {:.0}\n", remaining),
+        mode::GameMode::Zen => "Zen\n".to_string(),
+        mode::GameMode::TimeAttack => format!("Time Attack: {:.1}\n", remaining),
+        mode::GameMode::Survival => format!("Survived: {}:{:02}\n", elapsed / 60, elapsed % 60),
     }
 }
 
@@ -19,11 +57,14 @@ pub fn spawn_score_ui(
     score: Res<score::PlayerScore>,
     asset_server: Res<AssetServer>,
     time_query: Query<&level::types::TimeRemaining>,
+    curve: Res<level::difficulty::DifficultyCurve>,
+    game_mode: Res<mode::GameMode>,
 ) {
-    let time = match time_query.get_single() {
-        Ok(v) => v.remaining,
-        Err(_) => level::types::TimeRemaining::default().remaining,
-    };
+    let line = mode_line(
+        *game_mode,
+        time_query.get_single().ok(),
+        curve.starting_time,
+    );
 
     commands
         .spawn((
@@ -53,16 +94,186 @@ pub fn spawn_score_ui(
                         },
                     ),
                     TextSection::new(
-                        format!("Time: {}", time),
+                        line,
                         TextStyle {
                             font: asset_server.load("ui/fonts/font.otf"),
                             font_size: 35.0,
                             color: Color::from(basic::MAROON),
                         },
                     ),
+                    TextSection::new(
+                        format!("Sorted: {}  Wrong: {}\n", score.sorted, score.missorted),
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 20.0,
+                            color: Color::from(basic::MAROON),
+                        },
+                    ),
+                    TextSection::new(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 20.0,
+                            color: Color::from(basic::MAROON),
+                        },
+                    ),
                 ]),
                 types::ScoreText,
             ));
+
+            //Clutter meter, zen has no limit to fill up
+            if game_mode.clutter_limit().is_some() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(8.0),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "Clutter",
+                                TextStyle {
+                                    font: asset_server.load("ui/fonts/font.otf"),
+                                    font_size: 20.0,
+                                    color: Color::from(basic::MAROON),
+                                },
+                            ),
+                            types::ClutterText,
+                        ));
+
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Px(100.0),
+                                    height: Val::Px(8.0),
+                                    ..Default::default()
+                                },
+                                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.4)),
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    NodeBundle {
+                                        style: Style {
+                                            width: Val::Percent(0.0),
+                                            height: Val::Percent(100.0),
+                                            ..Default::default()
+                                        },
+                                        background_color: BackgroundColor(Color::from(
+                                            basic::MAROON,
+                                        )),
+                                        ..Default::default()
+                                    },
+                                    types::ClutterBar,
+                                ));
+                            });
+                    });
+            }
+
+            //Combo counter with a bar showing how long until it runs out
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(8.0),
+                            ..Default::default()
+                        },
+                        visibility: Visibility::Hidden,
+                        ..Default::default()
+                    },
+                    types::ComboDisplay,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: asset_server.load("ui/fonts/font.otf"),
+                                font_size: 25.0,
+                                color: Color::from(basic::OLIVE),
+                            },
+                        ),
+                        types::ComboText,
+                    ));
+
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(100.0),
+                                height: Val::Px(8.0),
+                                ..Default::default()
+                            },
+                            background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.4)),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Percent(100.0),
+                                        height: Val::Percent(100.0),
+                                        ..Default::default()
+                                    },
+                                    background_color: BackgroundColor(Color::from(basic::OLIVE)),
+                                    ..Default::default()
+                                },
+                                types::ComboBar,
+                            ));
+                        });
+                });
+
+            #[cfg(feature = "dev-tools")]
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("ui/fonts/font.otf"),
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                types::DifficultyDebugText,
+            ));
+
+            //Trick shot announcements
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("ui/fonts/font.otf"),
+                        font_size: 30.0,
+                        color: Color::from(basic::YELLOW),
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(20.0),
+                    width: Val::Percent(100.0),
+                    justify_self: JustifySelf::Center,
+                    ..Default::default()
+                })
+                .with_text_justify(JustifyText::Center),
+                types::TrickText,
+            ));
+
+            //Bin legend, one colored label per category
+            parent.spawn(TextBundle::from_sections(
+                level::types::TrashCategory::ALL.map(|category| {
+                    TextSection::new(
+                        format!("{}  ", category.name()),
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 18.0,
+                            color: category.color(),
+                        },
+                    )
+                }),
+            ));
         });
 }
 
@@ -70,14 +281,137 @@ pub fn update_score_ui(
     mut text_query: Query<&mut Text, With<types::ScoreText>>,
     score: Res<score::PlayerScore>,
     time_query: Query<&level::types::TimeRemaining>,
+    held_query: Query<&level::types::Trash, With<level::types::HeldObject>>,
+    curve: Res<level::difficulty::DifficultyCurve>,
+    game_mode: Res<mode::GameMode>,
+) {
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!("Score: {}\n", score.score);
+        text.sections[1].value = mode_line(
+            *game_mode,
+            time_query.get_single().ok(),
+            curve.starting_time,
+        );
+        text.sections[2].value = format!("Sorted: {}  Wrong: {}\n", score.sorted, score.missorted);
+
+        match held_query.iter().next() {
+            Some(trash) => {
+                text.sections[3].value = format!("Holding: {}", trash.category.name());
+                text.sections[3].style.color = trash.category.color();
+            }
+            None => text.sections[3].value.clear(),
+        }
+    }
+}
+
+//Fills up as trash piles up, blinking red once it gets close to overflowing
+pub fn update_clutter_ui(
+    time: Res<Time>,
+    clutter: Res<level::clutter::Clutter>,
+    mut text_query: Query<&mut Text, With<types::ClutterText>>,
+    mut bar_query: Query<(&mut Style, &mut BackgroundColor), With<types::ClutterBar>>,
 ) {
+    let color = if clutter.warning {
+        Color::from(basic::RED).with_alpha((time.elapsed_seconds() * 8.0).sin() * 0.3 + 0.7)
+    } else {
+        Color::from(basic::MAROON)
+    };
+
     for mut text in text_query.iter_mut() {
-        let time = match time_query.get_single() {
-            Ok(v) => v.remaining,
-            Err(_) => level::types::TimeRemaining::default().remaining,
+        text.sections[0].value = if clutter.warning {
+            format!("Clutter {}!", clutter.items)
+        } else {
+            format!("Clutter {}", clutter.items)
         };
+        text.sections[0].style.color = color;
+    }
 
-        text.sections[0].value = format!("Score: {}\n", score.score);
-        text.sections[1].value = format!("Time: {:.0}", time);
+    for (mut style, mut background_color) in bar_query.iter_mut() {
+        style.width = Val::Percent(clutter.fill.min(1.0) * 100.0);
+        background_color.0 = color;
+    }
+}
+
+pub fn update_combo_ui(
+    score: Res<score::PlayerScore>,
+    mut display_query: Query<&mut Visibility, With<types::ComboDisplay>>,
+    mut text_query: Query<&mut Text, With<types::ComboText>>,
+    mut bar_query: Query<&mut Style, With<types::ComboBar>>,
+) {
+    //A single item is not a combo yet
+    let active = score.combo >= 2;
+
+    for mut visibility in display_query.iter_mut() {
+        *visibility = if active {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+
+    if !active {
+        return;
+    }
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!(
+            "Combo {}  x{}  Streak {}",
+            score.combo,
+            score.combo_multiplier(),
+            score.streak
+        );
+    }
+
+    for mut style in bar_query.iter_mut() {
+        style.width = Val::Percent(score.combo_fraction() * 100.0);
+    }
+}
+
+//How long a trick label stays up, fading out over the last half
+const TRICK_LABEL_TIME: f32 = 1.5;
+
+pub fn update_trick_text(
+    time: Res<Time>,
+    mut remaining: Local<f32>,
+    mut binned_event_reader: EventReader<level::types::TrashBinnedEvent>,
+    mut text_query: Query<&mut Text, With<types::TrickText>>,
+) {
+    let labels: Vec<String> = binned_event_reader
+        .read()
+        .flat_map(|event| event.tricks.iter())
+        .map(|trick| format!("{} +{}", trick.label(), trick.points()))
+        .collect();
+
+    for mut text in text_query.iter_mut() {
+        if !labels.is_empty() {
+            text.sections[0].value = labels.join("\n");
+            *remaining = TRICK_LABEL_TIME;
+        }
+
+        *remaining = (*remaining - time.delta_seconds()).max(0.0);
+        let alpha = (*remaining / (TRICK_LABEL_TIME / 2.0)).min(1.0);
+        text.sections[0].style.color.set_alpha(alpha);
+    }
+}
+
+//Live difficulty curve values, only in dev builds
+#[cfg(feature = "dev-tools")]
+pub fn update_difficulty_debug(
+    mut text_query: Query<&mut Text, With<types::DifficultyDebugText>>,
+    score: Res<score::PlayerScore>,
+    time_query: Query<&level::types::TimeRemaining>,
+    curve: Res<level::difficulty::DifficultyCurve>,
+) {
+    let elapsed = time_query.get_single().map_or(0.0, |timer| timer.elapsed);
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!(
+            "Drain: x{:.2}  Spawn every: {:.2}s  Bonus: x{:.2}  Penalty: {:.1}s  Elapsed: {:.0}s",
+            curve.time_drain.evaluate(score.score, elapsed),
+            curve.spawn_every.evaluate(score.score, elapsed),
+            curve.time_bonus_scale.evaluate(score.score, elapsed),
+            curve.wrong_bin_penalty.evaluate(score.score, elapsed),
+            elapsed,
+        );
     }
 }
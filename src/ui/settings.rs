@@ -0,0 +1,299 @@
+use bevy::{color::palettes::basic, prelude::*};
+
+use crate::{audio, input, settings, state};
+
+use super::types;
+
+pub struct SettingsScreenPlugin;
+
+impl Plugin for SettingsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(state::GameState::Settings), spawn_menu_settings)
+            .add_systems(OnEnter(state::PauseState::Settings), spawn_pause_settings)
+            .add_systems(
+                Update,
+                (update_settings, update_setting_text).chain().run_if(
+                    in_state(state::GameState::Settings)
+                        .or_else(in_state(state::PauseState::Settings)),
+                ),
+            );
+    }
+}
+
+pub fn spawn_menu_settings(
+    commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<settings::Settings>,
+) {
+    spawn_settings(
+        commands,
+        &asset_server,
+        &settings,
+        StateScoped(state::GameState::Settings),
+        "Back to menu",
+    );
+}
+
+pub fn spawn_pause_settings(
+    commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<settings::Settings>,
+) {
+    spawn_settings(
+        commands,
+        &asset_server,
+        &settings,
+        StateScoped(state::PauseState::Settings),
+        "Back",
+    );
+}
+
+//The same screen opens from the menu and from the pause menu, only its scope differs
+fn spawn_settings(
+    mut commands: Commands,
+    asset_server: &AssetServer,
+    settings: &settings::Settings,
+    scope: impl Bundle,
+    back_label: &str,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                z_index: ZIndex::Global(2),
+                background_color: BackgroundColor(Color::BLACK),
+                ..Default::default()
+            },
+            scope,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Settings",
+                TextStyle {
+                    font: asset_server.load("ui/fonts/title_font.TTF"),
+                    font_size: 35.0,
+                    color: Color::from(basic::GREEN),
+                },
+            ));
+
+            for setting in settings::Setting::ALL {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Percent(60.0),
+                            height: Val::Percent(7.0),
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            setting.label(),
+                            TextStyle {
+                                font: asset_server.load("ui/fonts/font.otf"),
+                                font_size: 18.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(50.0),
+                                    height: Val::Percent(90.0),
+                                    flex_direction: FlexDirection::Row,
+                                    align_items: AlignItems::Center,
+                                    justify_content: JustifyContent::SpaceBetween,
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                for (arrow, up) in [("<", false), (">", true)] {
+                                    parent
+                                        .spawn((
+                                            ButtonBundle {
+                                                border_color: BorderColor(Color::from(
+                                                    basic::GREEN,
+                                                )),
+                                                border_radius: BorderRadius::all(Val::Px(3.0)),
+                                                style: Style {
+                                                    width: Val::Percent(15.0),
+                                                    height: Val::Percent(100.0),
+                                                    border: UiRect::all(Val::Px(3.0)),
+                                                    align_items: AlignItems::Center,
+                                                    justify_content: JustifyContent::Center,
+                                                    ..Default::default()
+                                                },
+                                                background_color: BackgroundColor(Color::from(
+                                                    basic::GREEN,
+                                                )),
+                                                ..Default::default()
+                                            },
+                                            types::SettingButton(setting, up, false),
+                                        ))
+                                        .with_children(|parent| {
+                                            parent.spawn(TextBundle::from_section(
+                                                arrow,
+                                                TextStyle {
+                                                    font: asset_server.load("ui/fonts/font.otf"),
+                                                    font_size: 18.0,
+                                                    color: Color::from(basic::LIME),
+                                                },
+                                            ));
+                                        });
+
+                                    //The value sits between the two arrows
+                                    if !up {
+                                        parent.spawn((
+                                            TextBundle::from_section(
+                                                settings.value_label(setting),
+                                                TextStyle {
+                                                    font: asset_server.load("ui/fonts/font.otf"),
+                                                    font_size: 18.0,
+                                                    color: Color::from(basic::LIME),
+                                                },
+                                            ),
+                                            types::SettingText(setting),
+                                        ));
+                                    }
+                                }
+                            });
+                    });
+            }
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        border_color: BorderColor(Color::from(basic::GREEN)),
+                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                        style: Style {
+                            width: Val::Percent(18.0),
+                            height: Val::Percent(6.5),
+                            border: UiRect::all(Val::Px(5.0)),
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            margin: UiRect::all(Val::Percent(1.0)),
+                            ..Default::default()
+                        },
+                        background_color: BackgroundColor(Color::from(basic::GREEN)),
+                        ..Default::default()
+                    },
+                    types::ReturnToMenuButton(false),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        back_label,
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 20.0,
+                            color: Color::from(basic::LIME),
+                        },
+                    ));
+                });
+        });
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn update_settings(
+    mut commands: Commands,
+    mut setting_button_query: Query<
+        (&Interaction, &mut BorderColor, &mut types::SettingButton),
+        Changed<Interaction>,
+    >,
+    mut return_to_menu_query: Query<
+        (
+            &Interaction,
+            &mut BorderColor,
+            &mut types::ReturnToMenuButton,
+        ),
+        Without<types::SettingButton>,
+    >,
+    mut settings: ResMut<settings::Settings>,
+    pause_state: Option<Res<State<state::PauseState>>>,
+    mut next_pause_state: ResMut<NextState<state::PauseState>>,
+    action_state: Res<input::types::ActionState>,
+    fade_to_black_query: Query<&types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    for (interaction, mut border_color, mut setting_button) in setting_button_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                setting_button.2 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !setting_button.2 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    setting_button.2 = true;
+                }
+            }
+            Interaction::Pressed => {
+                //Saved and applied by the settings plugin as soon as they change
+                settings.step(setting_button.0, setting_button.1);
+                sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+            }
+        }
+    }
+
+    let in_run = pause_state.is_some_and(|pause_state| *pause_state == state::PauseState::Settings);
+
+    let mut leave = action_state.just_pressed(input::types::Action::Back)
+        || (in_run && action_state.just_pressed(input::types::Action::Pause));
+
+    for (interaction, mut border_color, mut return_button) in return_to_menu_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                return_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !return_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    return_button.0 = true;
+                }
+            }
+            Interaction::Pressed => leave = true,
+        }
+    }
+
+    if !leave {
+        return;
+    }
+
+    //Back to the pause menu mid-run, back to the main menu otherwise
+    if in_run {
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+        next_pause_state.set(state::PauseState::Paused);
+    } else if fade_to_black_query.iter().count() == 0 {
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+        commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+    }
+}
+
+pub fn update_setting_text(
+    settings: Res<settings::Settings>,
+    mut setting_text_query: Query<(&mut Text, &types::SettingText)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (mut text, setting_text) in setting_text_query.iter_mut() {
+        text.sections[0].value = settings.value_label(setting_text.0);
+    }
+}
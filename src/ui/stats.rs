@@ -0,0 +1,184 @@
+use bevy::{color::palettes::basic, prelude::*};
+
+use crate::{audio, input, state, stats};
+
+use super::types;
+
+//Catalog items listed on the screen, most binned first
+const TOP_ITEMS: usize = 8;
+
+pub struct StatsScreenPlugin;
+
+impl Plugin for StatsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(state::GameState::Stats), spawn_stats)
+            .add_systems(
+                Update,
+                update_stats.run_if(in_state(state::GameState::Stats)),
+            );
+    }
+}
+
+fn duration_label(seconds: f64) -> String {
+    let seconds = seconds as u64;
+
+    if seconds >= 3600 {
+        format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+pub fn spawn_stats(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    lifetime_stats: Res<stats::LifetimeStats>,
+) {
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: asset_server.load("ui/fonts/font.otf"),
+        font_size,
+        color,
+    };
+
+    let mut items: Vec<(&String, &u64)> = lifetime_stats.binned.iter().collect();
+    items.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                z_index: ZIndex::Global(2),
+                background_color: BackgroundColor(Color::BLACK),
+                ..Default::default()
+            },
+            StateScoped(state::GameState::Stats),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Stats",
+                TextStyle {
+                    font: asset_server.load("ui/fonts/title_font.TTF"),
+                    font_size: 35.0,
+                    color: Color::from(basic::GREEN),
+                },
+            ));
+
+            parent.spawn(
+                TextBundle::from_sections([
+                    TextSection::new(
+                        format!(
+                            "Runs played: {}\nTime played: {}\nLongest run: {}\n",
+                            lifetime_stats.runs_played,
+                            duration_label(lifetime_stats.playtime),
+                            duration_label(lifetime_stats.longest_run as f64)
+                        ),
+                        text_style(20.0, Color::WHITE),
+                    ),
+                    TextSection::new(
+                        format!(
+                            "Best score: {}\nAverage score: {:.0}\n",
+                            lifetime_stats.best_score,
+                            lifetime_stats.average_score()
+                        ),
+                        text_style(20.0, Color::WHITE),
+                    ),
+                    TextSection::new(
+                        format!(
+                            "Trash binned: {}\nWrong bins: {}\nThrows binned: {} of {} ({:.0}%)\n\n",
+                            lifetime_stats.total_binned(),
+                            lifetime_stats.missorted,
+                            lifetime_stats.throws_binned,
+                            lifetime_stats.throws,
+                            lifetime_stats.throw_accuracy() * 100.0
+                        ),
+                        text_style(20.0, Color::WHITE),
+                    ),
+                    TextSection::new(
+                        items
+                            .iter()
+                            .take(TOP_ITEMS)
+                            .map(|(name, count)| format!("{}: {}", name, count))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        text_style(18.0, Color::from(basic::LIME)),
+                    ),
+                ])
+                .with_text_justify(JustifyText::Center),
+            );
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        border_color: BorderColor(Color::from(basic::GREEN)),
+                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                        style: Style {
+                            width: Val::Percent(18.0),
+                            height: Val::Percent(6.5),
+                            border: UiRect::all(Val::Px(5.0)),
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            margin: UiRect::all(Val::Percent(1.0)),
+                            ..Default::default()
+                        },
+                        background_color: BackgroundColor(Color::from(basic::GREEN)),
+                        ..Default::default()
+                    },
+                    types::ReturnToMenuButton(false),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back to menu",
+                        TextStyle {
+                            font: asset_server.load("ui/fonts/font.otf"),
+                            font_size: 20.0,
+                            color: Color::from(basic::LIME),
+                        },
+                    ));
+                });
+        });
+}
+
+pub fn update_stats(
+    mut commands: Commands,
+    mut return_to_menu_query: Query<(
+        &Interaction,
+        &mut BorderColor,
+        &mut types::ReturnToMenuButton,
+    )>,
+    action_state: Res<input::types::ActionState>,
+    fade_to_black_query: Query<&types::FadeToBlack>,
+    mut sfx_event_writer: EventWriter<audio::types::PlaySfx>,
+) {
+    let mut leave = action_state.just_pressed(input::types::Action::Back);
+
+    for (interaction, mut border_color, mut return_button) in return_to_menu_query.iter_mut() {
+        match *interaction {
+            Interaction::None => {
+                *border_color = BorderColor(Color::from(basic::GREEN));
+                return_button.0 = false;
+            }
+            Interaction::Hovered => {
+                *border_color = BorderColor(Color::from(basic::LIME));
+
+                if !return_button.0 {
+                    sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Hover));
+                    return_button.0 = true;
+                }
+            }
+            Interaction::Pressed => leave = true,
+        }
+    }
+
+    if leave && fade_to_black_query.iter().count() == 0 {
+        sfx_event_writer.send(audio::types::PlaySfx::new(audio::types::SoundId::Select));
+
+        commands.spawn(types::FadeToBlack::new(0.5, state::GameState::Menu));
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::state;
+use crate::{input, mode, replay, score, settings, state};
 
 #[derive(Component)]
 pub struct PlayButton(pub bool);
@@ -14,15 +14,94 @@ pub struct ReturnToMenuButton(pub bool);
 #[derive(Component)]
 pub struct CreditsButton(pub bool);
 
+#[derive(Component)]
+pub struct LeaderboardButton(pub bool);
+
+#[derive(Component)]
+pub struct LeaderboardTable;
+
+#[derive(Component)]
+pub struct StatsButton(pub bool);
+
+#[derive(Component)]
+pub struct AchievementsButton(pub bool);
+
+#[derive(Component)]
+pub struct SettingsButton(pub bool);
+
+//Setting it changes, whether it steps up or down, hovered
+#[derive(Component)]
+pub struct SettingButton(pub settings::Setting, pub bool, pub bool);
+
+#[derive(Component)]
+pub struct SettingText(pub settings::Setting);
+
+#[derive(Component)]
+pub struct ControlsButton(pub bool);
+
+//Action, which of its binding slots and whether it is hovered
+#[derive(Component)]
+pub struct RebindButton(pub input::types::Action, pub usize, pub bool);
+
+#[derive(Component)]
+pub struct BindingText(pub input::types::Action, pub usize);
+
+#[derive(Component)]
+pub struct ResetBindingsButton(pub bool);
+
+#[derive(Component)]
+pub struct ModeButton(pub bool);
+
+#[derive(Component)]
+pub struct ModeText;
+
+#[derive(Component)]
+pub struct HighscoreText;
+
+#[derive(Component)]
+pub struct LevelButton(pub bool);
+
+#[derive(Component)]
+pub struct LevelText;
+
+#[derive(Component)]
+pub struct SeedButton(pub bool);
+
+#[derive(Component)]
+pub struct SeedText;
+
 #[derive(Component)]
 pub struct Cursor;
 
+//Physics stand-in for one pointer, what actually holds on to trash
 #[derive(Component)]
-pub struct GhostCursor;
+pub struct GhostCursor(pub replay::types::PointerId);
 
 #[derive(Component)]
 pub struct ScoreText;
 
+#[derive(Component)]
+pub struct ClutterText;
+
+#[derive(Component)]
+pub struct ClutterBar;
+
+#[derive(Component)]
+pub struct ComboDisplay;
+
+#[derive(Component)]
+pub struct ComboText;
+
+#[derive(Component)]
+pub struct ComboBar;
+
+#[derive(Component)]
+pub struct TrickText;
+
+#[cfg(feature = "dev-tools")]
+#[derive(Component)]
+pub struct DifficultyDebugText;
+
 #[derive(Component)]
 pub struct FadeToBlack {
     duration: f32,
@@ -44,10 +123,58 @@ impl FadeToBlack {
     }
 }
 
-#[derive(Event)]
+//Why a run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOverReason {
+    TimeUp,
+    //Too much trash piled up outside the bins
+    Overflow,
+    //Ended from the pause menu
+    Quit,
+}
+
+impl GameOverReason {
+    pub fn title(&self) -> &'static str {
+        match self {
+            GameOverReason::TimeUp => "Time's up!",
+            GameOverReason::Overflow => "Overflowing!",
+            GameOverReason::Quit => "Run ended",
+        }
+    }
+}
+
+#[derive(Event, Clone)]
 pub struct GameOverEvent {
-    pub final_score: u64,
+    pub reason: GameOverReason,
+    pub summary: score::RunSummary,
+}
+
+//The run that just ended, kept for the game over screen
+#[derive(Resource, Clone)]
+pub struct LastRun {
+    pub reason: GameOverReason,
+    pub summary: score::RunSummary,
+    pub new_highscore: bool,
+    //Place on the mode's leaderboard, if the run made it
+    pub rank: Option<usize>,
 }
 
+//The player is typing a name for the leaderboard entry at `rank`
+#[derive(Resource)]
+pub struct NameEntry {
+    pub mode: mode::GameMode,
+    pub rank: usize,
+    pub name: String,
+}
+
+#[derive(Component)]
+pub struct NameEntryText;
+
+#[derive(Component)]
+pub struct RetryButton(pub bool);
+
+#[derive(Component)]
+pub struct EndRunButton(pub bool);
+
 #[derive(Component)]
-pub struct GlobalMusic;
+pub struct RestartButton(pub bool);